@@ -0,0 +1,9 @@
+#![no_main]
+
+// EDNSOption::parse must never panic, whatever the input.
+use libfuzzer_sys::fuzz_target;
+use drasil_dns::fuzzing::parse_edns_option;
+
+fuzz_target!(|data: &[u8]| {
+  let _ = parse_edns_option(data);
+});
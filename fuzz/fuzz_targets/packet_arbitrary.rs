@@ -0,0 +1,12 @@
+#![no_main]
+
+// Writing any structurally generated packet (and parsing the result) must never panic.
+use libfuzzer_sys::fuzz_target;
+use drasil_dns::Packet;
+
+fuzz_target!(|packet: Packet| {
+  if let Ok(bytes) = packet.to_bytes() {
+    let _ = Packet::parse(&bytes);
+  }
+  let _ = packet.to_bytes_with_limit(512);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+// Packet::parse must never panic, whatever the input. Any crash found here is a bug.
+use libfuzzer_sys::fuzz_target;
+use drasil_dns::Packet;
+
+fuzz_target!(|data: &[u8]| {
+  let _ = Packet::parse(data);
+});
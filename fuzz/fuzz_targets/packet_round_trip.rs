@@ -0,0 +1,13 @@
+#![no_main]
+
+// Any packet that parses must be written back and parsed again into the same packet.
+use libfuzzer_sys::fuzz_target;
+use drasil_dns::Packet;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(packet) = Packet::parse(data) {
+    let bytes = packet.to_bytes().expect("Failed to write parsed packet");
+    let reparsed = Packet::parse(&bytes).expect("Failed to parse written packet");
+    assert_eq!(packet, reparsed, "Packet not equal after parse+write+parse");
+  }
+});
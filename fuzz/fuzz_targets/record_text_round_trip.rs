@@ -0,0 +1,13 @@
+#![no_main]
+
+// Any record that parses from text must be displayed and parsed again into the same record.
+use libfuzzer_sys::fuzz_target;
+use drasil_dns::Record;
+
+fuzz_target!(|data: &str| {
+  if let Ok(record) = data.parse::<Record>() {
+    let text = record.to_string();
+    let reparsed: Record = text.parse().expect("Failed to parse displayed record");
+    assert_eq!(record, reparsed, "Record not equal after parse+display+parse");
+  }
+});
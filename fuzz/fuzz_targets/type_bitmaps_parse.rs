@@ -0,0 +1,9 @@
+#![no_main]
+
+// Parsing NSEC/NSEC3 type bitmaps must never panic, whatever the input.
+use libfuzzer_sys::fuzz_target;
+use drasil_dns::fuzzing::parse_type_bitmaps;
+
+fuzz_target!(|data: &[u8]| {
+  let _ = parse_type_bitmaps(data);
+});
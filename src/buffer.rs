@@ -1,47 +1,54 @@
 
 // ===== Imports =====
-use crate::error::DrasilDNSError;
+use std::{borrow::Cow, collections::HashMap};
+use crate::{error::DrasilDNSError, name::Name};
 // ===================
 
+/// Largest offset which can be referenced by a compression pointer (14 bits)
+const MAX_POINTER_OFFSET: usize = 0x3FFF;
+
 /// # Buffer
 /// Custom reader and writer for byte buffers.
 /// All operations are atomic for both reads and writes.
-pub(crate) struct Buffer {
+/// A buffer created from a slice borrows it, the data is only copied once something is written.
+pub(crate) struct Buffer<'a> {
   pos: usize,
-  data: Vec<u8>,
+  data: Cow<'a, [u8]>,
   expandable: bool,
+  names: Option<HashMap<Name, usize>>, // previously written name suffixes, used for compression
 }
 
-impl Default for Buffer {
+impl Default for Buffer<'_> {
   fn default() -> Self {
-    Self { pos: 0, data: vec![0; 512], expandable: false } // DNS packet size is 512 bytes acc. to original spec
+    Self { pos: 0, data: Cow::Owned(vec![0; 512]), expandable: false, names: None } // DNS packet size is 512 bytes acc. to original spec
   }
 }
 
-impl From<&[u8]> for Buffer {
-  fn from(data: &[u8]) -> Self {
-    Self { pos: 0, data: data.to_vec(), expandable: false }
+impl<'a> From<&'a [u8]> for Buffer<'a> {
+  fn from(data: &'a [u8]) -> Self {
+    Self { pos: 0, data: Cow::Borrowed(data), expandable: false, names: None }
   }
 }
 
-impl From<Vec<u8>> for Buffer {
+impl From<Vec<u8>> for Buffer<'_> {
   fn from(data: Vec<u8>) -> Self {
-    Self { pos: 0, data, expandable: false }
+    Self { pos: 0, data: Cow::Owned(data), expandable: false, names: None }
   }
 }
 
-impl Into<Vec<u8>> for Buffer {
-  fn into(mut self) -> Vec<u8> {
-    self.data.resize(self.pos, 0);
-    self.data
+impl From<Buffer<'_>> for Vec<u8> {
+  fn from(buff: Buffer<'_>) -> Self {
+    let mut data = buff.data.into_owned();
+    data.resize(buff.pos, 0);
+    data
   }
 }
 
 #[allow(dead_code)]
-impl Buffer {
+impl Buffer<'_> {
   /// Creates a new `Buffer` with given capacity
   pub fn with_capacity(capacity: usize) -> Self {
-    Self { pos: 0, data: vec![0; capacity], expandable: false }
+    Self { pos: 0, data: Cow::Owned(vec![0; capacity]), expandable: false, names: None }
   }
 
   /// Used to set the expandable nature of the `Buffer`
@@ -54,6 +61,13 @@ impl Buffer {
     self.expandable
   }
 
+  /// Enables name compression for writes done using `write_labels_compressed`
+  pub fn enable_compression(&mut self) {
+    if self.names.is_none() {
+      self.names = Some(HashMap::new());
+    }
+  }
+
   /// Tells whether `Buffer` is at EOF position
   pub fn is_eof(&self) -> bool {
     self.pos >= self.data.len() && !self.expandable
@@ -77,10 +91,10 @@ impl Buffer {
         return Err(DrasilDNSError::EOF);
       }
 
-      self.data.resize(at + bytes.len(), 0);
+      self.data.to_mut().resize(at + bytes.len(), 0);
     }
 
-    self.data[at..(at + bytes.len())].copy_from_slice(bytes);
+    self.data.to_mut()[at..(at + bytes.len())].copy_from_slice(bytes);
     self.pos = at + bytes.len();
     Ok(())
   }
@@ -91,6 +105,15 @@ impl Buffer {
     Ok(())
   }
 
+  /// Sets given slice data in the buffer from the provided position without moving the current position.
+  /// Useful for filling in length fields after the data they describe has been written.
+  pub fn patch_bytes(&mut self, at: usize, bytes: &[u8]) -> Result<(), DrasilDNSError> {
+    let pos = self.pos;
+    self.set_bytes(at, bytes)?;
+    self.pos = pos;
+    Ok(())
+  }
+
   /// Write given bytes starting from current position
   pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DrasilDNSError> {
     self.set_bytes(self.pos, bytes)?;
@@ -178,9 +201,31 @@ impl Buffer {
     Ok(())
   }
 
+  /// Reads a `<character-string>` (RFC1035 section 3.3), i.e. a single length octet followed by that many bytes
+  pub fn read_character_string(&mut self) -> Result<Vec<u8>, DrasilDNSError> {
+    self.read_transaction(|buff| {
+      let len = buff.read_u8()?;
+      Ok(buff.read_bytes(len as usize)?.to_vec())
+    })
+  }
+
+  /// Writes a `<character-string>` (RFC1035 section 3.3), fails if it is longer than 255 bytes
+  pub fn write_character_string(&mut self, data: &[u8]) -> Result<(), DrasilDNSError> {
+    if data.len() > u8::MAX as usize {
+      return Err(DrasilDNSError::CharacterStringTooLarge { size: data.len() });
+    }
+
+    let mut b = Buffer::with_capacity(1 + data.len());
+    b.write_u8(data.len() as u8)?;
+    b.write_bytes(data)?;
+
+    self.write_buffer(&b)?;
+    Ok(())
+  }
+
   /// Reads series of labels stored in buffer starting from current position.
-  /// Returns the number of bytes read and the labels.
-  pub fn read_labels(&mut self, use_jumps: bool) -> Result<(usize, Vec<String>), DrasilDNSError> {
+  /// Returns the number of bytes read and the name formed by the labels.
+  pub fn read_labels(&mut self, use_jumps: bool) -> Result<(usize, Name), DrasilDNSError> {
     let initial_pos = self.pos;
 
     let mut labels = vec![];
@@ -225,7 +270,7 @@ impl Buffer {
           break;
         } else if len > 63 {
           self.pos = initial_pos;
-          return Err(DrasilDNSError::LabelTooLarge { size: len });
+          return Err(DrasilDNSError::LabelTooLarge { size: len as usize });
         }
 
         let mut buff = vec![];
@@ -239,39 +284,88 @@ impl Buffer {
           });
         }
 
-        labels.push(String::from_utf8_lossy(&buff).to_lowercase());
+        labels.push(buff);
         i += len as usize;
       }
     }
 
+    let name = match Name::from_labels(labels) {
+      Ok(name) => name,
+      Err(e) => {
+        self.pos = initial_pos;
+        return Err(e);
+      },
+    };
+
     if jumps == 0 {
       self.seek(i);
     }
 
-    Ok((self.pos - initial_pos, labels))
+    Ok((self.pos - initial_pos, name))
   }
 
-  /// Writes the provided labels to the buffer
-  pub fn write_labels(&mut self, labels: &Vec<String>) -> Result<usize, DrasilDNSError> {
+  /// Writes labels of the provided name to the buffer
+  pub fn write_labels(&mut self, name: &Name) -> Result<usize, DrasilDNSError> {
     let initial_pos = self.pos;
     let mut b = Buffer::with_capacity(0);
     b.set_expandable(true);
 
-    for label in labels {
-      let label_bytes = label.as_bytes();
-      let len = label_bytes.len() as u8;
+    for label in name.labels() {
+      b.write_u8(label.len() as u8)?;
+      b.write_bytes(label)?;
+    }
+
+    b.write_u8(0)?;
+
+    self.write_buffer(&b)?;
+    Ok(self.pos - initial_pos)
+  }
+
+  /// Writes labels of the provided name to the buffer, compressing them according to RFC1035 (section 4.1.4).
+  /// Longest suffix of the name which has already been written is replaced with a pointer to it, while the new suffixes are remembered for later names.
+  /// Behaves exactly like `write_labels` in case compression is not enabled for the buffer.
+  pub fn write_labels_compressed(&mut self, name: &Name) -> Result<usize, DrasilDNSError> {
+    let names = match &self.names {
+      Some(names) => names,
+      None => return self.write_labels(name),
+    };
+
+    let initial_pos = self.pos;
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
 
-      if len > 63 {
-        return Err(DrasilDNSError::LabelTooLarge { size: len });
+    let mut pointer = None;
+    let mut suffixes = vec![];
+    let mut suffix = name.clone();
+
+    while let Some(parent) = suffix.parent() {
+      if let Some(&offset) = names.get(&suffix) {
+        pointer = Some(offset);
+        break;
+      }
+
+      let offset = initial_pos + b.pos();
+      let label = suffix.labels().next().unwrap_or_default(); // non-root names always have a label
+      b.write_u8(label.len() as u8)?;
+      b.write_bytes(label)?;
+
+      if offset <= MAX_POINTER_OFFSET {
+        suffixes.push((suffix, offset));
       }
 
-      b.write_u8(len)?;
-      b.write_bytes(label_bytes)?;
+      suffix = parent;
     }
 
-    b.write_u8(0)?;
+    match pointer {
+      Some(offset) => b.write_u16(0xC000 | offset as u16)?,
+      None => b.write_u8(0)?,
+    }
 
     self.write_buffer(&b)?;
+    if let Some(names) = &mut self.names {
+      names.extend(suffixes);
+    }
+
     Ok(self.pos - initial_pos)
   }
 
@@ -289,6 +383,23 @@ impl Buffer {
       },
     }
   }
+
+  /// Helper method for atomic writes to `Buffer`.
+  /// Takes in a function and in case of a failure resets the `pos` counter and forgets any compression targets written by it.
+  pub fn write_transaction<T, F>(&mut self, op: F) -> Result<T, DrasilDNSError>
+  where F: FnOnce(&mut Self) -> Result<T, DrasilDNSError> {
+    let pos = self.pos;
+    match op(self) {
+      Ok(v) => Ok(v),
+      Err(e) => {
+        self.pos = pos;
+        if let Some(names) = &mut self.names {
+          names.retain(|_, offset| *offset < pos);
+        }
+        Err(e)
+      },
+    }
+  }
 }
 
 #[cfg(test)]
@@ -319,6 +430,11 @@ mod tests {
     assert_eq!(v1, 0x5012, "Failed at read_u16");
     assert_eq!(v2, 0x34, "Failed at read_u8");
     assert_eq!(v3, &data[..], "Failed at read_bytes");
+    assert!(matches!(b.data, Cow::Borrowed(_)), "Data copied by reads");
+
+    b.seek(0);
+    b.write_u8(0x51).expect("Failed at write_u8");
+    assert_eq!((&b.data[..], &data[..]), (&[0x51, 0x12, 0x34][..], &[0x50, 0x12, 0x34][..]), "Write not done on a copy");
 
     Ok(())
   }
@@ -349,15 +465,34 @@ mod tests {
     let mut b = Buffer::with_capacity(0);
     b.set_expandable(true);
 
-    let test_labels = vec![
-      "google".to_string(),
-      "com".to_string(),
-    ];
+    let test_name: Name = "google.com".parse().unwrap();
 
-    b.write_labels(&test_labels).expect("Failed at write_labels");
+    b.write_labels(&test_name).expect("Failed at write_labels");
 
     b.seek(0);
-    let (_, labels) = b.read_labels(false).expect("Failed at read_labels");
-    assert_eq!(labels, test_labels, "Labels not equal")
+    let (_, name) = b.read_labels(false).expect("Failed at read_labels");
+    assert_eq!(name, test_name, "Labels not equal")
+  }
+
+  #[test]
+  fn buffer_label_compression() {
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
+    b.enable_compression();
+
+    let first: Name = "www.google.com".parse().unwrap();
+    let second: Name = "mail.Google.com".parse().unwrap();
+
+    b.write_labels_compressed(&first).expect("Failed at write_labels_compressed");
+    let len = b.write_labels_compressed(&second).expect("Failed at write_labels_compressed");
+    assert_eq!(len, 7, "Suffix was not replaced by a pointer");
+
+    let bytes: Vec<u8> = b.into();
+    assert_eq!(&bytes[16..], &[4, b'm', b'a', b'i', b'l', 0xC0, 4], "Pointer not written correctly");
+
+    let mut b: Buffer = bytes.into();
+    b.read_labels(true).expect("Failed at read_labels");
+    let (_, name) = b.read_labels(true).expect("Failed at read_labels");
+    assert_eq!(name.to_string(), "mail.google.com.", "Labels not equal");
   }
 }
\ No newline at end of file
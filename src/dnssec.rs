@@ -0,0 +1,307 @@
+
+/// Provides verification of RRSIG records (RFC4035 section 5.3)
+#[cfg(feature = "dnssec-verify")]
+pub mod verify;
+
+/// Provides zone signing: DNSKEY, RRSIG and NSEC or NSEC3 records (RFC4035 section 2, RFC5155 section 7.1)
+#[cfg(feature = "dnssec-sign")]
+pub mod sign;
+
+// ===== Imports =====
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384};
+use crate::{
+  buffer::Buffer,
+  error::DrasilDNSError,
+  name::Name,
+  record::{rrset::RRset, Record},
+  types::{dnssec::{DNSSECAlgorithm, DNSSECDigestType}, RecordType},
+};
+// ===================
+
+impl Record {
+  /// Returns the key tag of a `DNSKEY` record (RFC4034 appendix B), as found in the DS and RRSIG records referring to it.
+  /// For RSA/MD5 keys (algorithm 1) this is the second to last 16 bits of the public key modulus (appendix B.1).
+  /// Returns `None` if this is not a `DNSKEY` record.
+  pub fn key_tag(&self) -> Option<u16> {
+    let Self::DNSKEY { flags, protocol, algorithm, public_key, .. } = self else {
+      return None;
+    };
+
+    if *algorithm == DNSSECAlgorithm::RSAMD5 {
+      let len = public_key.len();
+      return Some(match len {
+        0..=2 => 0,
+        _ => u16::from_be_bytes([public_key[len - 3], public_key[len - 2]]),
+      });
+    }
+
+    let [flags_high, flags_low] = flags.to_be_bytes();
+    let rdata = [flags_high, flags_low, *protocol, u8::from(*algorithm)];
+
+    let mut ac: u32 = 0;
+    for (i, byte) in rdata.iter().chain(public_key).enumerate() {
+      ac += match i % 2 {
+        0 => (*byte as u32) << 8,
+        _ => *byte as u32,
+      };
+    }
+    ac += (ac >> 16) & 0xFFFF;
+
+    Some(ac as u16)
+  }
+
+  /// Tells whether an `NSEC3` record has the Opt-Out flag set (RFC5155 section 3.1.2.1), which is the least significant bit of its flags.
+  /// Returns `None` if this is not an `NSEC3` record.
+  pub fn opt_out(&self) -> Option<bool> {
+    match self {
+      Self::NSEC3 { flags, .. } => Some(flags & 0b1 == 1),
+      _ => None,
+    }
+  }
+
+  /// Builds the `DS` record for `owner` referring to a `DNSKEY` record (RFC4034 section 5.1.4), with the class and TTL of the key.
+  /// The digest is taken over the canonical (lowercased) owner name followed by the DNSKEY RDATA, so `owner` should be the owner
+  /// name of the key (the zone apex) rather than the name it is delegated from.
+  /// Fails with `DrasilDNSError::InvalidData` if `dnskey` is not a `DNSKEY` record or the digest type is not
+  /// one of SHA-1, SHA-256 and SHA-384.
+  pub fn ds_from_dnskey(owner: &Name, dnskey: &Record, digest_type: DNSSECDigestType) -> Result<Record, DrasilDNSError> {
+    let (Self::DNSKEY { class, ttl, algorithm, .. }, Some(key_tag)) = (dnskey, dnskey.key_tag()) else {
+      return Err(DrasilDNSError::InvalidData { msg: format!("expected a DNSKEY record, found {}", dnskey.record_type()) });
+    };
+
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
+    b.write_labels(&owner.to_lowercase())?;
+    b.write_bytes(&dnskey.rdata()?)?;
+    let data: Vec<u8> = b.into();
+
+    let digest = match digest_type {
+      DNSSECDigestType::SHA1 => Sha1::digest(&data).to_vec(),
+      DNSSECDigestType::SHA256 => Sha256::digest(&data).to_vec(),
+      DNSSECDigestType::SHA384 => Sha384::digest(&data).to_vec(),
+      _ => return Err(DrasilDNSError::InvalidData { msg: format!("unsupported DS digest type {}", u8::from(digest_type)) }),
+    };
+
+    Ok(Self::DS {
+      domain: owner.clone(),
+      class: *class,
+      ttl: *ttl,
+      key_tag,
+      algorithm: *algorithm,
+      digest_type,
+      digest,
+    })
+  }
+
+  /// Returns the RDATA of the record in canonical form (RFC4034 section 6.2): names are never compressed,
+  /// and lowercased for the types listed there (as amended by RFC6840 section 5.1, which removes NSEC).
+  pub fn to_canonical_rdata(&self) -> Result<Vec<u8>, DrasilDNSError> {
+    let mut record = self.clone();
+
+    match &mut record {
+      Self::NS { host, .. } | Self::CNAME { host, .. } | Self::PTR { host, .. } | Self::MX { host, .. } => {
+        *host = host.to_lowercase();
+      },
+      Self::DNAME { target, .. } | Self::SRV { target, .. } => *target = target.to_lowercase(),
+      Self::NAPTR { replacement, .. } => *replacement = replacement.to_lowercase(),
+      Self::RRSIG { signer_name, .. } => *signer_name = signer_name.to_lowercase(),
+      Self::SOA { mname, rname, .. } => {
+        *mname = mname.to_lowercase();
+        *rname = rname.to_lowercase();
+      },
+      _ => {},
+    }
+
+    record.rdata()
+  }
+}
+
+impl Name {
+  /// Returns the hash of the name used as the owner name of NSEC3 records (RFC5155 section 5): SHA-1 over the
+  /// canonical (lowercased) wire form of the name and the salt, hashed again with the salt `iterations` more times.
+  pub fn nsec3_hash(&self, iterations: u16, salt: &[u8]) -> Result<Vec<u8>, DrasilDNSError> {
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
+    b.write_labels(&self.to_lowercase())?;
+    let name: Vec<u8> = b.into();
+
+    let mut hash = Sha1::new().chain_update(name).chain_update(salt).finalize();
+    for _ in 0..iterations {
+      hash = Sha1::new().chain_update(hash).chain_update(salt).finalize();
+    }
+
+    Ok(hash.to_vec())
+  }
+}
+
+impl RRset {
+  /// Returns the data signed by an RRSIG record covering this RRset (RFC4034 section 3.1.8.1): the RRSIG RDATA
+  /// without the signature, followed by the records in canonical form and order, all with the original TTL of the RRSIG.
+  /// If the RRSIG has fewer labels than the owner name, the RRset was synthesized from a wildcard and the
+  /// owner name is reduced to `*.` and the rightmost labels (RFC4035 section 5.3.2).
+  /// Fails with `DrasilDNSError::InvalidData` if `rrsig` is not an RRSIG record covering this RRset.
+  pub fn signed_data(&self, rrsig: &Record) -> Result<Vec<u8>, DrasilDNSError> {
+    let Record::RRSIG { type_covered, labels, original_ttl, signer_name, .. } = rrsig else {
+      return Err(DrasilDNSError::InvalidData { msg: format!("expected an RRSIG record, found {}", rrsig.record_type()) });
+    };
+
+    if RecordType::from(*type_covered) != self.record_type() || rrsig.class() != self.class() {
+      return Err(DrasilDNSError::InvalidData {
+        msg: format!("RRSIG covering {} doesn't cover RRset {} {}", RecordType::from(*type_covered), self.class(), self.record_type()),
+      });
+    }
+
+    // the leading `*` label of wildcard owner names is not counted (RFC4034 section 3.1.3)
+    let name = self.name().to_lowercase();
+    let count = name.label_count() - name.labels().next().is_some_and(|l| l == b"*") as usize;
+    let labels = *labels as usize;
+    let owner = match labels {
+      _ if labels > count => {
+        return Err(DrasilDNSError::InvalidData { msg: format!("RRSIG has more labels ({}) than the owner name {}", labels, name) });
+      },
+      _ if labels < count => Name::from_labels(std::iter::once(&b"*"[..]).chain(name.labels().skip(name.label_count() - labels)))?,
+      _ => name,
+    };
+
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
+
+    let mut header = rrsig.clone();
+    if let Record::RRSIG { signature, signer_name: header_signer, .. } = &mut header {
+      signature.clear();
+      *header_signer = signer_name.to_lowercase();
+    }
+    b.write_bytes(&header.rdata()?)?;
+
+    let mut rrset = self.clone();
+    rrset.sort_canonical()?;
+
+    for record in rrset.records() {
+      let rdata = record.to_canonical_rdata()?;
+      let len = u16::try_from(rdata.len())
+        .map_err(|_| DrasilDNSError::InvalidRDataLength { record_type: record.record_type().into(), size: rdata.len() })?;
+
+      b.write_labels(&owner)?;
+      b.write_u16(record.record_type().into())?;
+      b.write_u16(record.class().into())?;
+      b.write_u32(*original_ttl)?;
+      b.write_u16(len)?;
+      b.write_bytes(&rdata)?;
+    }
+
+    Ok(b.into())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::types::RecordClass;
+  use super::*;
+
+  #[test]
+  fn dnskey_key_tag_and_ds() {
+    // examples of RFC4034 section 5.4, RFC4509 section 2.3 and RFC6605 section 6.2
+    let key: Record = "dskey.example.com. 86400 IN DNSKEY 256 3 5 AQOeiiR0GOMYkDshWoSKz9XzfwJr1AYtsmx3TGkJaNXVbfi/\
+      2pHm822aJ5iI9BMzNXxeYCmZDRD99WYwYqUSdjMmmAphXdvxegXd/M5+X7OrzKBaMbCVdFLUUh6DhweJBjEVv5f2wwjM9XzcnOf+EPbtG9DMBmADjFDc2w/rljwvFw==".parse().unwrap();
+    assert_eq!(key.key_tag(), Some(60485), "Incorrect key tag");
+
+    let ds = Record::ds_from_dnskey(key.domain(), &key, DNSSECDigestType::SHA1).expect("Failed to build DS record");
+    assert_eq!(ds, "dskey.example.com. 86400 IN DS 60485 5 1 2BB183AF5F22588179A53B0A98631FAD1A292118".parse().unwrap());
+
+    let ds = Record::ds_from_dnskey(key.domain(), &key, DNSSECDigestType::SHA256).expect("Failed to build DS record");
+    assert_eq!(ds, "dskey.example.com. 86400 IN DS 60485 5 2 D4B7D520E7BB5F0F67674A0CCEB1E3E0614B93C4F9E99B8383F6A1E4469DA50A".parse().unwrap());
+
+    let key: Record = "Example.NET. 3600 IN DNSKEY 257 3 14 xKYaNhWdGOfJ+nPrL8/arkwf2EY3MDJ+SErKivBVSum1w/egsXvSADtNJhyem5RCOpgQ6K8X1DRSEkrbYQ+OB+v8/uX45NBwY8rp65F6Glur8I/mlVNgF6W/qTI37m40".parse().unwrap();
+    let ds = Record::ds_from_dnskey(key.domain(), &key, DNSSECDigestType::SHA384).expect("Failed to build DS record");
+    assert_eq!(ds, "Example.NET. 3600 IN DS 10771 14 4 72d7b62976ce06438e9c0bf319013cf801f09ecc84b8d7e9495f27e305c6a9b0563a9b5f4d288405c3008a946df983d6".parse().unwrap());
+
+    // the REVOKE flag is part of the key tag (RFC5011 section 2.1)
+    let revoked: Record = "Example.NET. 3600 IN DNSKEY 385 3 14 xKYaNhWdGOfJ+nPrL8/arkwf2EY3MDJ+SErKivBVSum1w/egsXvSADtNJhyem5RCOpgQ6K8X1DRSEkrbYQ+OB+v8/uX45NBwY8rp65F6Glur8I/mlVNgF6W/qTI37m40".parse().unwrap();
+    assert_eq!(revoked.key_tag(), Some(10899), "Incorrect key tag for revoked key");
+    assert_eq!(revoked.to_string().split(' ').nth(4), Some("385"), "REVOKE flag not kept");
+
+    // RSA/MD5 keys use bits of the modulus instead of the checksum
+    let key: Record = "example.com. 300 IN DNSKEY 256 3 1 AQIDBAUG".parse().unwrap();
+    assert_eq!(key.key_tag(), Some(0x0405), "Incorrect RSA/MD5 key tag");
+
+    assert!(Record::ds_from_dnskey(key.domain(), &key, DNSSECDigestType::Unknown(200)).is_err(), "Unsupported digest type accepted");
+    let not_a_key: Record = "example.com. 300 IN A 192.0.2.1".parse().unwrap();
+    assert_eq!(not_a_key.key_tag(), None);
+    assert!(Record::ds_from_dnskey(not_a_key.domain(), &not_a_key, DNSSECDigestType::SHA256).is_err(), "DS built from a non-DNSKEY record");
+  }
+
+  #[test]
+  fn nsec3_hashes() {
+    // hashes of the example zone of RFC5155 appendix A (salt aabbccdd, 12 iterations)
+    let salt = [0xaa, 0xbb, 0xcc, 0xdd];
+    for (name, hash) in [("example", "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom"), ("A.Example", "35mthgpgcu1qg68fab165klnsnk3dpvl")] {
+      let name: Name = name.parse().unwrap();
+      let digest = name.nsec3_hash(12, &salt).expect("Failed to hash name");
+      assert_eq!(crate::presentation::fmt_base32hex(&digest), hash, "Incorrect NSEC3 hash of {}", name);
+    }
+  }
+
+  #[test]
+  fn canonical_form() {
+    let mx: Record = "Example.com. 300 IN MX 10 Mail.Example.COM.".parse().unwrap();
+    assert_eq!(mx.to_canonical_rdata().unwrap(), b"\x00\x0a\x04mail\x07example\x03com\x00", "MX target not lowercased");
+    let nsec: Record = "example.com. 300 IN NSEC Host.example.com. A".parse().unwrap();
+    assert_eq!(nsec.to_canonical_rdata().unwrap(), nsec.rdata().unwrap(), "NSEC next name lowercased");
+
+    let records: Vec<Record> = [
+      "a.example.com. 300 IN A 192.0.2.2",
+      "b.example.com. 300 IN A 192.0.2.1",
+      "A.example.com. 60 IN A 192.0.2.1",
+      "a.example.com. 300 IN A 192.0.2.2",
+    ].iter().map(|r| r.parse().unwrap()).collect();
+
+    let mut rrsets = RRset::group(records);
+    assert_eq!(rrsets.len(), 2, "Records not grouped by owner name");
+    assert_eq!(rrsets[0].ttl(), 60);
+
+    rrsets[0].sort_canonical().unwrap();
+    let addrs: Vec<String> = rrsets[0].records().iter().map(|r| r.rdata_to_string().unwrap()).collect();
+    assert_eq!(addrs, ["192.0.2.1", "192.0.2.2"], "RRset not sorted canonically or duplicates kept");
+    let mixed: Vec<Record> = rrsets.iter().flat_map(|set| set.records().to_vec()).collect();
+    assert!(RRset::new(vec![]).is_err() && RRset::new(mixed).is_err(), "Invalid RRset accepted");
+
+    // a record whose RDATA can't be written fails the sort without touching the RRset
+    let txt = |strings: Vec<Vec<u8>>| Record::TXT { domain: "example.com".parse().unwrap(), strings, ttl: 60, class: RecordClass::IN };
+    let mut rrset = RRset::new(vec![txt(vec![b"b".to_vec()]), txt(vec![vec![b'a'; 300]]), txt(vec![b"a".to_vec()])]).unwrap();
+    let before = rrset.clone();
+    rrset.sort_canonical().expect_err("Record with an oversized character string sorted");
+    assert_eq!(rrset, before, "RRset changed by a failed sort");
+
+    // a wildcard expansion signed with two labels, the owner name is replaced by `*.example.com.`
+    let rrsig = Record::RRSIG {
+      domain: "a.example.com".parse().unwrap(),
+      class: RecordClass::IN,
+      ttl: 60,
+      type_covered: RecordType::A.into(),
+      algorithm: DNSSECAlgorithm::ECDSAP256SHA256,
+      labels: 2,
+      original_ttl: 3600,
+      signature_expiration: 2,
+      signature_inception: 1,
+      key_tag: 0x1234,
+      signer_name: "Example.com".parse().unwrap(),
+      signature: vec![0xFF; 64],
+    };
+
+    let owner = b"\x01*\x07example\x03com\x00";
+    let mut expected = b"\x00\x01\x0d\x02\x00\x00\x0e\x10\x00\x00\x00\x02\x00\x00\x00\x01\x12\x34\x07example\x03com\x00".to_vec();
+    for last in [1, 2] {
+      expected.extend(owner);
+      expected.extend([0, 1, 0, 1, 0, 0, 0x0e, 0x10, 0, 4, 192, 0, 2, last]);
+    }
+    assert_eq!(rrsets[0].signed_data(&rrsig).unwrap(), expected, "Incorrect signed data");
+
+    let mut rrsig = rrsig;
+    if let Record::RRSIG { labels, .. } = &mut rrsig {
+      *labels = 4;
+    }
+    assert!(rrsets[0].signed_data(&rrsig).is_err(), "RRSIG with too many labels accepted");
+    assert!(rrsets[0].signed_data(&mx).is_err(), "Non-RRSIG record accepted");
+  }
+}
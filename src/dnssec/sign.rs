@@ -0,0 +1,550 @@
+
+// ===== Imports =====
+use std::{collections::{BTreeMap, HashSet}, fmt};
+use ed25519_dalek::Signer;
+use rand_core::OsRng;
+use crate::{
+  error::DrasilDNSError,
+  name::Name,
+  presentation,
+  record::{rrset::RRset, Record},
+  types::{dnssec::DNSSECAlgorithm, RecordClass, RecordType},
+};
+// ===================
+
+/// NSEC3 hash algorithm number of SHA-1, the only one defined (RFC5155 section 11)
+const NSEC3_SHA1: u8 = 1;
+
+fn invalid(msg: String) -> DrasilDNSError {
+  DrasilDNSError::InvalidData { msg }
+}
+
+#[derive(Clone)]
+enum KeyPair {
+  ED25519(ed25519_dalek::SigningKey),
+  ECDSAP256SHA256(p256::ecdsa::SigningKey),
+}
+
+/// # DNSSEC Key
+/// Private key of a zone, used to sign its RRsets. Supports Ed25519 (RFC8080) and ECDSA P-256/SHA-256 (RFC6605) keys.
+/// Keys with the secure entry point flag (key signing keys) only sign the DNSKEY RRset when the zone also has keys
+/// without it (zone signing keys) of the same algorithm.
+#[derive(Clone)]
+pub struct DNSSECKey {
+  pair: KeyPair,
+  is_secure_entry_point: bool,
+}
+
+impl DNSSECKey {
+  /// Generates a new key of the provided algorithm with the operating system's random number generator.
+  /// Fails with `DrasilDNSError::UnsupportedAlgorithm` for algorithms other than ED25519 and ECDSAP256SHA256.
+  pub fn generate(algorithm: DNSSECAlgorithm) -> Result<Self, DrasilDNSError> {
+    let pair = match algorithm {
+      DNSSECAlgorithm::ED25519 => KeyPair::ED25519(ed25519_dalek::SigningKey::generate(&mut OsRng)),
+      DNSSECAlgorithm::ECDSAP256SHA256 => KeyPair::ECDSAP256SHA256(p256::ecdsa::SigningKey::random(&mut OsRng)),
+      _ => return Err(DrasilDNSError::UnsupportedAlgorithm { algorithm: algorithm.into() }),
+    };
+
+    Ok(Self { pair, is_secure_entry_point: false })
+  }
+
+  /// Loads a key of the provided algorithm from its 32 octet private key, as returned by `private_key`
+  /// (the Ed25519 seed of RFC8032 section 5.1.5 or the ECDSA private scalar, big-endian).
+  /// Fails with `DrasilDNSError::UnsupportedAlgorithm` for algorithms other than ED25519 and ECDSAP256SHA256,
+  /// and with `DrasilDNSError::InvalidData` if the private key is malformed.
+  pub fn from_private_key(algorithm: DNSSECAlgorithm, private_key: &[u8]) -> Result<Self, DrasilDNSError> {
+    let malformed = || invalid(format!("malformed {} private key", algorithm));
+
+    let pair = match algorithm {
+      DNSSECAlgorithm::ED25519 => {
+        let seed = <&[u8; 32]>::try_from(private_key).map_err(|_| malformed())?;
+        KeyPair::ED25519(ed25519_dalek::SigningKey::from_bytes(seed))
+      },
+      DNSSECAlgorithm::ECDSAP256SHA256 => {
+        KeyPair::ECDSAP256SHA256(p256::ecdsa::SigningKey::from_slice(private_key).map_err(|_| malformed())?)
+      },
+      _ => return Err(DrasilDNSError::UnsupportedAlgorithm { algorithm: algorithm.into() }),
+    };
+
+    Ok(Self { pair, is_secure_entry_point: false })
+  }
+
+  /// Sets the secure entry point flag of the key, making it a key signing key
+  pub fn secure_entry_point(mut self) -> Self {
+    self.is_secure_entry_point = true;
+    self
+  }
+
+  /// Returns the algorithm of the key
+  pub fn algorithm(&self) -> DNSSECAlgorithm {
+    match self.pair {
+      KeyPair::ED25519(_) => DNSSECAlgorithm::ED25519,
+      KeyPair::ECDSAP256SHA256(_) => DNSSECAlgorithm::ECDSAP256SHA256,
+    }
+  }
+
+  /// Tells whether the key has the secure entry point flag set
+  pub fn is_secure_entry_point(&self) -> bool {
+    self.is_secure_entry_point
+  }
+
+  /// Returns the private key, to be stored and loaded again with `from_private_key`
+  pub fn private_key(&self) -> Vec<u8> {
+    match &self.pair {
+      KeyPair::ED25519(key) => key.to_bytes().to_vec(),
+      KeyPair::ECDSAP256SHA256(key) => key.to_bytes().to_vec(),
+    }
+  }
+
+  /// Returns the public key in the form used by DNSKEY records. ECDSA keys are the uncompressed point
+  /// without its 0x04 prefix (RFC6605 section 4), Ed25519 keys are 32 octets (RFC8080 section 3).
+  pub fn public_key(&self) -> Vec<u8> {
+    match &self.pair {
+      KeyPair::ED25519(key) => key.verifying_key().to_bytes().to_vec(),
+      KeyPair::ECDSAP256SHA256(key) => key.verifying_key().to_encoded_point(false).as_bytes()[1..].to_vec(),
+    }
+  }
+
+  /// Returns the DNSKEY record of the key for the provided zone
+  pub fn dnskey(&self, zone: &Name, class: RecordClass, ttl: u32) -> Record {
+    Record::DNSKEY {
+      domain: zone.clone(),
+      class,
+      ttl,
+      flags: 0x0100 | self.is_secure_entry_point as u16,
+      protocol: 3,
+      algorithm: self.algorithm(),
+      public_key: self.public_key(),
+    }
+  }
+
+  /// Signs the data, both algorithms produce deterministic signatures (RFC8032 and RFC6979).
+  /// ECDSA signatures are r | s (RFC6605 section 4).
+  pub fn sign(&self, data: &[u8]) -> Vec<u8> {
+    match &self.pair {
+      KeyPair::ED25519(key) => key.sign(data).to_bytes().to_vec(),
+      KeyPair::ECDSAP256SHA256(key) => {
+        let signature: p256::ecdsa::Signature = key.sign(data);
+        signature.to_bytes().to_vec()
+      },
+    }
+  }
+}
+
+impl fmt::Debug for DNSSECKey {
+  /// Shows the algorithm and flags of the key, never the private key
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("DNSSECKey")
+      .field("algorithm", &self.algorithm())
+      .field("is_secure_entry_point", &self.is_secure_entry_point)
+      .finish_non_exhaustive()
+  }
+}
+
+#[derive(Debug, Clone)]
+struct NSEC3Chain {
+  iterations: u16,
+  salt: Vec<u8>,
+  opt_out: bool,
+}
+
+/// # Zone Signer
+/// Signs the records of a zone (RFC4035 section 2): adds the DNSKEY records of its keys at the apex, an NSEC chain
+/// (or an NSEC3 chain along with an NSEC3PARAM record) and an RRSIG per key algorithm over every authoritative RRset.
+/// Delegation points only get signatures over their DS and NSEC RRsets, and records below them (glue) are left unsigned.
+#[derive(Debug, Clone)]
+pub struct ZoneSigner {
+  keys: Vec<DNSSECKey>,
+  inception: u32,
+  expiration: u32,
+  dnskey_ttl: Option<u32>,
+  nsec3: Option<NSEC3Chain>,
+}
+
+impl ZoneSigner {
+  /// Creates a signer using the provided keys, with signatures valid from `inception` to `expiration`
+  /// (seconds since the UNIX epoch). It builds an NSEC chain unless `with_nsec3` is used.
+  pub fn new(keys: Vec<DNSSECKey>, inception: u32, expiration: u32) -> Self {
+    Self {
+      keys,
+      inception,
+      expiration,
+      dnskey_ttl: None,
+      nsec3: None,
+    }
+  }
+
+  /// Sets the TTL of the DNSKEY records, the TTL of the SOA record is used by default
+  pub fn with_dnskey_ttl(mut self, ttl: u32) -> Self {
+    self.dnskey_ttl = Some(ttl);
+    self
+  }
+
+  /// Builds an NSEC3 chain with SHA-1 hashes and the provided parameters instead of an NSEC chain (RFC5155 section 7.1).
+  /// With opt-out, delegations without DS records are left out of the chain.
+  pub fn with_nsec3(mut self, iterations: u16, salt: Vec<u8>, opt_out: bool) -> Self {
+    self.nsec3 = Some(NSEC3Chain { iterations, salt, opt_out });
+    self
+  }
+
+  /// Signs the records of a zone, which must have exactly one SOA record at its apex and no records outside of it.
+  /// RRSIG, NSEC, NSEC3, NSEC3PARAM and OPT records are dropped from the input and DNSKEY records are kept next to those of the keys.
+  /// Returns the signed zone in canonical order of owner names, every RRset followed by its signatures.
+  /// Fails with `DrasilDNSError::InvalidData` if there are no keys or the records don't form a zone.
+  pub fn sign(&self, records: impl IntoIterator<Item = Record>) -> Result<Vec<Record>, DrasilDNSError> {
+    if self.keys.is_empty() {
+      return Err(invalid("a zone can't be signed without keys".to_string()));
+    }
+
+    let records: Vec<Record> = records
+      .into_iter()
+      .filter(|r| !matches!(r, Record::RRSIG { .. } | Record::NSEC { .. } | Record::NSEC3 { .. } | Record::NSEC3PARAM { .. } | Record::OPT { .. }))
+      .collect();
+
+    let mut soas = records.iter().filter_map(|r| match r {
+      Record::SOA { domain, class, ttl, minimum, .. } => Some((domain.clone(), *class, *ttl, *minimum)),
+      _ => None,
+    });
+    let (Some((apex, class, soa_ttl, minimum)), None) = (soas.next(), soas.next()) else {
+      return Err(invalid("a zone needs exactly one SOA record".to_string()));
+    };
+
+    if let Some(r) = records.iter().find(|r| !r.domain().is_subdomain_of(&apex) || r.class() != class) {
+      return Err(invalid(format!("record {} {} {} is outside of zone {} {}", r.domain(), r.class(), r.record_type(), apex, class)));
+    }
+
+    // negative answers are cached for the lower of the SOA TTL and minimum (RFC9077 section 3)
+    let negative_ttl = soa_ttl.min(minimum);
+    let dnskey_ttl = self.dnskey_ttl.unwrap_or(soa_ttl);
+    let dnskeys = self.keys.iter().map(|key| key.dnskey(&apex, class, dnskey_ttl));
+
+    let mut rrsets = RRset::group(records.into_iter().chain(dnskeys));
+    for rrset in &mut rrsets {
+      rrset.sort_canonical()?;
+    }
+
+    // names below a zone cut or a DNAME are not authoritative (RFC4035 section 2.2, RFC6672 section 2.3)
+    let cuts: Vec<Name> = rrsets
+      .iter()
+      .filter(|set| (set.record_type() == RecordType::NS && *set.name() != apex) || set.record_type() == RecordType::DNAME)
+      .map(|set| set.name().clone())
+      .collect();
+    let occluded = |name: &Name| cuts.iter().any(|cut| name != cut && name.is_subdomain_of(cut));
+
+    let mut names: BTreeMap<Name, HashSet<RecordType>> = BTreeMap::new();
+    for rrset in rrsets.iter().filter(|set| !occluded(set.name())) {
+      names.entry(rrset.name().clone()).or_default().insert(rrset.record_type());
+    }
+
+    let denial = match &self.nsec3 {
+      None => Self::nsec_chain(&names, &apex, class, negative_ttl),
+      Some(chain) => Self::nsec3_chain(chain, names.clone(), &apex, class, negative_ttl)?,
+    };
+    rrsets.extend(RRset::group(denial));
+    rrsets.sort_by(|a, b| (a.name(), u16::from(a.record_type())).cmp(&(b.name(), u16::from(b.record_type()))));
+
+    let key_tags: Vec<u16> = self.keys.iter().map(|key| key.dnskey(&apex, class, dnskey_ttl).key_tag().unwrap_or(0)).collect();
+    let mut signed = vec![];
+
+    for rrset in rrsets {
+      let name = rrset.name();
+      let delegation = *name != apex && names.get(name).is_some_and(|types| types.contains(&RecordType::NS));
+      let is_signed = !occluded(name) && (!delegation || matches!(rrset.record_type(), RecordType::DS | RecordType::NSEC));
+
+      let mut rrsigs = vec![];
+      if is_signed {
+        for (key, key_tag) in self.keys.iter().zip(&key_tags) {
+          if self.signs(key, rrset.record_type()) {
+            rrsigs.push(self.rrsig(&rrset, key, *key_tag, &apex)?);
+          }
+        }
+      }
+
+      signed.extend(rrset.into_records());
+      signed.extend(rrsigs);
+    }
+
+    Ok(signed)
+  }
+
+  /// Tells whether the key signs RRsets of the provided type. Every key signs the DNSKEY RRset, the other RRsets are
+  /// signed by the keys without the secure entry point flag, or by all keys of an algorithm which only has key signing keys.
+  fn signs(&self, key: &DNSSECKey, record_type: RecordType) -> bool {
+    record_type == RecordType::DNSKEY
+      || !key.is_secure_entry_point()
+      || self.keys.iter().all(|k| k.algorithm() != key.algorithm() || k.is_secure_entry_point())
+  }
+
+  /// Builds the RRSIG record of an RRset made with the provided key (RFC4034 section 3.1)
+  fn rrsig(&self, rrset: &RRset, key: &DNSSECKey, key_tag: u16, apex: &Name) -> Result<Record, DrasilDNSError> {
+    // the leading `*` label of wildcard owner names is not counted (RFC4034 section 3.1.3)
+    let name = rrset.name();
+    let labels = name.label_count() - name.labels().next().is_some_and(|l| l == b"*") as usize;
+
+    let mut rrsig = Record::RRSIG {
+      domain: name.clone(),
+      class: rrset.class(),
+      ttl: rrset.ttl(),
+      type_covered: rrset.record_type().into(),
+      algorithm: key.algorithm(),
+      labels: labels as u8,
+      original_ttl: rrset.ttl(),
+      signature_expiration: self.expiration,
+      signature_inception: self.inception,
+      key_tag,
+      signer_name: apex.clone(),
+      signature: vec![],
+    };
+
+    let data = rrset.signed_data(&rrsig)?;
+    if let Record::RRSIG { signature, .. } = &mut rrsig {
+      *signature = key.sign(&data);
+    }
+
+    Ok(rrsig)
+  }
+
+  /// Links the authoritative names and delegation points in canonical order, the last one pointing back to the apex
+  /// (RFC4035 section 2.3). Their type maps list the types present along with RRSIG and NSEC.
+  fn nsec_chain(names: &BTreeMap<Name, HashSet<RecordType>>, apex: &Name, class: RecordClass, ttl: u32) -> Vec<Record> {
+    let next_names = names.keys().skip(1).chain(std::iter::once(apex));
+
+    names.iter().zip(next_names).map(|((name, types), next)| {
+      let mut record_types = types.clone();
+      record_types.extend([RecordType::RRSIG, RecordType::NSEC]);
+
+      Record::NSEC { domain: name.clone(), class, ttl, next_domain_name: next.clone(), record_types }
+    }).collect()
+  }
+
+  /// Links the hashes of the authoritative names, delegation points and empty non-terminals in order, the last one
+  /// pointing back to the first, and adds the NSEC3PARAM record at the apex (RFC5155 section 7.1).
+  /// Names with signed RRsets get the RRSIG type in their type maps, empty non-terminals have none.
+  fn nsec3_chain(
+    chain: &NSEC3Chain,
+    mut names: BTreeMap<Name, HashSet<RecordType>>,
+    apex: &Name,
+    class: RecordClass,
+    ttl: u32,
+  ) -> Result<Vec<Record>, DrasilDNSError> {
+    let insecure = |name: &Name, types: &HashSet<RecordType>| {
+      name != apex && types.contains(&RecordType::NS) && !types.contains(&RecordType::DS)
+    };
+
+    if chain.opt_out {
+      names.retain(|name, types| !insecure(name, types));
+    }
+
+    // every type present at a name other than an insecure delegation is signed
+    for (name, types) in names.iter_mut() {
+      if !insecure(name, types) {
+        types.insert(RecordType::RRSIG);
+      }
+    }
+    names.entry(apex.clone()).or_default().insert(RecordType::NSEC3PARAM);
+
+    let ancestors: Vec<Name> = names
+      .keys()
+      .filter(|name| *name != apex)
+      .flat_map(|name| std::iter::successors(name.parent(), |n| n.parent()).take_while(|n| n != apex))
+      .collect();
+    for name in ancestors {
+      names.entry(name).or_default();
+    }
+
+    let mut hashes: BTreeMap<Vec<u8>, (Name, HashSet<RecordType>)> = BTreeMap::new();
+    for (name, types) in names {
+      let hash = name.nsec3_hash(chain.iterations, &chain.salt)?;
+      if let Some((other, _)) = hashes.get(&hash) {
+        return Err(invalid(format!("NSEC3 hashes of {} and {} collide, another salt is needed", other, name)));
+      }
+      hashes.insert(hash, (name, types));
+    }
+
+    let salt_length = u8::try_from(chain.salt.len())
+      .map_err(|_| invalid(format!("NSEC3 salt too long ({} octets)", chain.salt.len())))?;
+
+    let next_hashes = hashes.keys().skip(1).chain(hashes.keys().take(1));
+    let mut records = hashes.iter().zip(next_hashes).map(|((hash, (_, types)), next)| {
+      let owner = presentation::fmt_base32hex(hash);
+
+      Ok(Record::NSEC3 {
+        domain: Name::from_labels(std::iter::once(owner.as_bytes()).chain(apex.labels()))?,
+        class,
+        ttl,
+        hash_algorithm: NSEC3_SHA1,
+        flags: chain.opt_out as u8,
+        iterations: chain.iterations,
+        salt_length,
+        salt: chain.salt.clone(),
+        hash_length: next.len() as u8,
+        next_hashed_owner_name: next.clone(),
+        record_types: types.clone(),
+      })
+    }).collect::<Result<Vec<_>, DrasilDNSError>>()?;
+
+    records.push(Record::NSEC3PARAM {
+      domain: apex.clone(),
+      class,
+      ttl,
+      hash_algorithm: NSEC3_SHA1,
+      flags: 0,
+      iterations: chain.iterations,
+      salt_length,
+      salt: chain.salt.clone(),
+    });
+
+    Ok(records)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::zone::ZoneParser;
+  use super::*;
+
+  const ZONE: &str = "
+$ORIGIN example.com.
+$TTL 3600
+@       IN  SOA   ns1 hostmaster 2024010101 7200 3600 1209600 300
+        IN  NS    ns1
+ns1     IN  A     192.0.2.1
+www     IN  A     192.0.2.2
+        IN  AAAA  2001:db8::2
+*.w     IN  TXT   \"wildcard\"
+a.b     IN  MX    10 mail.example.net.
+sub     IN  NS    ns.sub
+        IN  DS    60485 13 2 D4B7D520E7BB5F0F67674A0CCEB1E3E0614B93C4F9E99B8383F6A1E4469DA50A
+ns.sub  IN  A     192.0.2.3
+ins     IN  NS    ns.example.net.
+";
+
+  const INCEPTION: u32 = 1_700_000_000;
+  const EXPIRATION: u32 = 1_702_592_000;
+
+  fn keys() -> Vec<DNSSECKey> {
+    let ksk = DNSSECKey::from_private_key(DNSSECAlgorithm::ED25519, &[7; 32]).unwrap().secure_entry_point();
+    let zsk = DNSSECKey::from_private_key(DNSSECAlgorithm::ED25519, &[8; 32]).unwrap();
+    let csk = DNSSECKey::from_private_key(DNSSECAlgorithm::ECDSAP256SHA256, &[9; 32]).unwrap().secure_entry_point();
+    vec![ksk, zsk, csk]
+  }
+
+  /// Verifies every RRSIG of the signed zone and returns the signed owner names and types, along with the key tags of the signers
+  fn verify_zone(signed: &[Record]) -> Vec<(String, RecordType, u16)> {
+    let dnskeys: Vec<&Record> = signed.iter().filter(|r| r.record_type() == RecordType::DNSKEY).collect();
+    let rrsets = RRset::group(signed.iter().filter(|r| r.record_type() != RecordType::RRSIG).cloned());
+    let mut covered = vec![];
+
+    for rrsig in signed.iter().filter(|r| r.record_type() == RecordType::RRSIG) {
+      let Record::RRSIG { type_covered, key_tag, .. } = rrsig else { unreachable!() };
+      let rrset = rrsets.iter()
+        .find(|set| set.name() == rrsig.domain() && u16::from(set.record_type()) == *type_covered)
+        .expect("RRSIG without RRset");
+      let dnskey = dnskeys.iter().find(|k| k.key_tag() == Some(*key_tag)).expect("RRSIG without DNSKEY");
+
+      let result = rrset.verify(rrsig, dnskey, u64::from(INCEPTION) + 86400);
+      assert!(result.is_ok(), "Failed to verify {}: {:?}", rrsig, result);
+
+      covered.push((rrsig.domain().to_string(), RecordType::from(*type_covered), *key_tag));
+    }
+
+    covered
+  }
+
+  #[test]
+  fn sign_zone_nsec() {
+    let records = ZoneParser::new().parse(ZONE).expect("Failed to parse zone");
+    let signed = ZoneSigner::new(keys(), INCEPTION, EXPIRATION).sign(records).expect("Failed to sign zone");
+    let covered = verify_zone(&signed);
+
+    assert_eq!(signed.iter().filter(|r| r.record_type() == RecordType::DNSKEY).count(), 3, "DNSKEY records missing");
+    let signers = |name: &str, record_type| covered.iter().filter(|c| c.0 == name && c.1 == record_type).count();
+    assert_eq!(signers("example.com.", RecordType::DNSKEY), 3, "DNSKEY RRset not signed by every key");
+    assert_eq!(signers("example.com.", RecordType::SOA), 2, "SOA not signed by the ZSK and the ECDSA CSK");
+    let ksk_tag = keys()[0].dnskey(&"example.com".parse().unwrap(), RecordClass::IN, 3600).key_tag().unwrap();
+    assert!(covered.iter().all(|c| c.2 != ksk_tag || c.1 == RecordType::DNSKEY), "KSK signed a non-DNSKEY RRset");
+
+    assert_eq!(signers("sub.example.com.", RecordType::DS), 2, "DS not signed");
+    assert_eq!(signers("sub.example.com.", RecordType::NS), 0, "Delegation NS signed");
+    assert_eq!(signers("ns.sub.example.com.", RecordType::A), 0, "Glue signed");
+    assert_eq!(signers("*.w.example.com.", RecordType::TXT), 2, "Wildcard not signed");
+
+    let chain: Vec<(String, String)> = signed.iter().filter_map(|r| match r {
+      Record::NSEC { domain, next_domain_name, .. } => Some((domain.to_string(), next_domain_name.to_string())),
+      _ => None,
+    }).collect();
+    let names = ["example.com.", "a.b.example.com.", "ins.example.com.", "ns1.example.com.", "sub.example.com.", "*.w.example.com.", "www.example.com."];
+    let expected: Vec<(String, String)> = names.iter().zip(names.iter().cycle().skip(1)).map(|(a, b)| (a.to_string(), b.to_string())).collect();
+    assert_eq!(chain, expected, "Incorrect NSEC chain");
+
+    let Some(Record::NSEC { record_types, .. }) = signed.iter().find(|r| r.record_type() == RecordType::NSEC) else { unreachable!() };
+    let apex_types = [RecordType::SOA, RecordType::NS, RecordType::DNSKEY, RecordType::RRSIG, RecordType::NSEC];
+    assert_eq!(record_types, &HashSet::from(apex_types), "Incorrect apex type map");
+  }
+
+  #[test]
+  fn sign_zone_nsec3_opt_out() {
+    let records = ZoneParser::new().parse(ZONE).expect("Failed to parse zone");
+    let signed = ZoneSigner::new(keys(), INCEPTION, EXPIRATION)
+      .with_nsec3(0, vec![0xaa, 0xbb], true)
+      .sign(records)
+      .expect("Failed to sign zone");
+    verify_zone(&signed);
+
+    let apex: Name = "example.com".parse().unwrap();
+    let param = signed.iter().find(|r| r.record_type() == RecordType::NSEC3PARAM).expect("NSEC3PARAM missing");
+    assert_eq!(param.rdata_to_string().unwrap(), "1 0 0 AABB");
+    assert_eq!(param.domain(), &apex);
+    assert_eq!(param.ttl(), 300, "NSEC3PARAM TTL is not the SOA minimum");
+
+    let chain: Vec<(Vec<u8>, &Record)> = signed.iter().filter_map(|r| match r {
+      Record::NSEC3 { domain, .. } => Some((presentation::parse_base32hex(std::str::from_utf8(domain.labels().next().unwrap()).unwrap()).unwrap(), r)),
+      _ => None,
+    }).collect();
+
+    // the insecure delegation is opted out, the empty non-terminal `b` is added
+    let names = ["example.com", "a.b.example.com", "b.example.com", "ns1.example.com", "sub.example.com", "*.w.example.com", "w.example.com", "www.example.com"];
+    let mut hashes: Vec<Vec<u8>> = names.iter().map(|n| n.parse::<Name>().unwrap().nsec3_hash(0, &[0xaa, 0xbb]).unwrap()).collect();
+    hashes.sort();
+    assert_eq!(chain.iter().map(|(hash, _)| hash.clone()).collect::<Vec<_>>(), hashes, "Incorrect NSEC3 owner names");
+
+    for (i, (_, record)) in chain.iter().enumerate() {
+      let Record::NSEC3 { next_hashed_owner_name, .. } = record else { unreachable!() };
+      assert_eq!(record.opt_out(), Some(true), "Opt-out flag not set");
+      assert_eq!(next_hashed_owner_name, &hashes[(i + 1) % hashes.len()], "NSEC3 chain not linked in hash order");
+    }
+
+    let types = |name: &str| {
+      let hash = name.parse::<Name>().unwrap().nsec3_hash(0, &[0xaa, 0xbb]).unwrap();
+      match chain.iter().find(|(h, _)| *h == hash) {
+        Some((_, Record::NSEC3 { record_types, .. })) => record_types.clone(),
+        _ => panic!("No NSEC3 record for {}", name),
+      }
+    };
+    assert!(types("b.example.com").is_empty(), "Empty non-terminal has types");
+    assert!(types("example.com").contains(&RecordType::NSEC3PARAM), "NSEC3PARAM missing from apex type map");
+    assert_eq!(types("sub.example.com"), HashSet::from([RecordType::NS, RecordType::DS, RecordType::RRSIG]));
+  }
+
+  #[test]
+  fn signing_keys() {
+    for algorithm in [DNSSECAlgorithm::ED25519, DNSSECAlgorithm::ECDSAP256SHA256] {
+      let key = DNSSECKey::generate(algorithm).expect("Failed to generate key");
+      let loaded = DNSSECKey::from_private_key(algorithm, &key.private_key()).expect("Failed to load key");
+      assert_eq!(key.public_key(), loaded.public_key(), "Loaded key differs");
+      assert_eq!(key.public_key().len(), algorithm.public_key_len().unwrap(), "Incorrect public key length");
+
+      let signature = key.sign(b"data");
+      assert_eq!(signature, loaded.sign(b"data"), "Signatures not deterministic");
+      assert!(crate::dnssec::verify::verify_signature(algorithm, &key.public_key(), b"data", &signature).is_ok(), "Invalid signature");
+    }
+
+    assert!(matches!(DNSSECKey::generate(DNSSECAlgorithm::RSASHA256), Err(DrasilDNSError::UnsupportedAlgorithm { algorithm: 8 })));
+    assert!(DNSSECKey::from_private_key(DNSSECAlgorithm::ED25519, &[1; 31]).is_err(), "Malformed key accepted");
+
+    let signer = ZoneSigner::new(vec![], INCEPTION, EXPIRATION);
+    assert!(signer.sign(ZoneParser::new().parse(ZONE).unwrap()).is_err(), "Zone signed without keys");
+    let signer = ZoneSigner::new(keys(), INCEPTION, EXPIRATION);
+    assert!(signer.sign(vec!["example.com. 300 IN A 192.0.2.1".parse().unwrap()]).is_err(), "Zone without SOA signed");
+  }
+}
@@ -0,0 +1,240 @@
+
+// ===== Imports =====
+use ed25519_dalek::Verifier;
+use rsa::{BigUint, Pkcs1v15Sign, RsaPublicKey};
+use sha2::{Digest, Sha256, Sha512};
+use crate::{
+  error::DrasilDNSError,
+  record::{rrset::RRset, Record},
+  types::{dnssec::DNSSECAlgorithm, RecordType},
+};
+// ===================
+
+fn invalid(msg: String) -> DrasilDNSError {
+  DrasilDNSError::InvalidSignature { msg }
+}
+
+/// Tells whether serial number `a` is before or equal to `b` in 32-bit serial number arithmetic (RFC1982),
+/// which RRSIG timestamps use so they can wrap around (RFC4034 section 3.1.5)
+fn serial_le(a: u32, b: u32) -> bool {
+  b.wrapping_sub(a) as i32 >= 0
+}
+
+/// Splits an RSA public key into its exponent and modulus (RFC3110 section 2).
+/// The exponent length is one octet, or three octets starting with a zero when it doesn't fit.
+fn rsa_key(public_key: &[u8]) -> Result<RsaPublicKey, DrasilDNSError> {
+  let (len, rest) = match public_key {
+    [0, high, low, rest @ ..] => (u16::from_be_bytes([*high, *low]) as usize, rest),
+    [len, rest @ ..] => (*len as usize, rest),
+    [] => return Err(invalid("empty RSA public key".to_string())),
+  };
+
+  if len == 0 || rest.len() <= len {
+    return Err(invalid(format!("malformed RSA public key (exponent length: {}, key length: {})", len, public_key.len())));
+  }
+
+  let (exponent, modulus) = rest.split_at(len);
+  RsaPublicKey::new(BigUint::from_bytes_be(modulus), BigUint::from_bytes_be(exponent))
+    .map_err(|e| invalid(format!("unusable RSA public key: {}", e)))
+}
+
+/// Verifies a signature made with the provided algorithm and DNSKEY public key over some data.
+/// Supports RSA/SHA-256, RSA/SHA-512 (RFC5702), ECDSA P-256/SHA-256, ECDSA P-384/SHA-384 (RFC6605) and Ed25519 (RFC8080).
+/// Fails with `DrasilDNSError::UnsupportedAlgorithm` for any other algorithm,
+/// and with `DrasilDNSError::InvalidSignature` if the key is malformed or the signature doesn't match.
+pub fn verify_signature(algorithm: DNSSECAlgorithm, public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<(), DrasilDNSError> {
+  let mismatch = || invalid(format!("{} signature doesn't match the data", algorithm));
+
+  match algorithm {
+    DNSSECAlgorithm::RSASHA256 => {
+      rsa_key(public_key)?.verify(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(data), signature).map_err(|_| mismatch())
+    },
+    DNSSECAlgorithm::RSASHA512 => {
+      rsa_key(public_key)?.verify(Pkcs1v15Sign::new::<Sha512>(), &Sha512::digest(data), signature).map_err(|_| mismatch())
+    },
+
+    // ECDSA keys are the uncompressed point without its 0x04 prefix, signatures are r | s (RFC6605 section 4)
+    DNSSECAlgorithm::ECDSAP256SHA256 => {
+      let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&[&[0x04], public_key].concat())
+        .map_err(|_| invalid("malformed ECDSA P-256 public key".to_string()))?;
+      let signature = p256::ecdsa::Signature::from_slice(signature)
+        .map_err(|_| invalid("malformed ECDSA P-256 signature".to_string()))?;
+      key.verify(data, &signature).map_err(|_| mismatch())
+    },
+    DNSSECAlgorithm::ECDSAP384SHA384 => {
+      let key = p384::ecdsa::VerifyingKey::from_sec1_bytes(&[&[0x04], public_key].concat())
+        .map_err(|_| invalid("malformed ECDSA P-384 public key".to_string()))?;
+      let signature = p384::ecdsa::Signature::from_slice(signature)
+        .map_err(|_| invalid("malformed ECDSA P-384 signature".to_string()))?;
+      key.verify(data, &signature).map_err(|_| mismatch())
+    },
+
+    DNSSECAlgorithm::ED25519 => {
+      let key = <&[u8; 32]>::try_from(public_key).ok()
+        .and_then(|key| ed25519_dalek::VerifyingKey::from_bytes(key).ok())
+        .ok_or_else(|| invalid("malformed Ed25519 public key".to_string()))?;
+      let signature = ed25519_dalek::Signature::from_slice(signature)
+        .map_err(|_| invalid("malformed Ed25519 signature".to_string()))?;
+      key.verify_strict(data, &signature).map_err(|_| mismatch())
+    },
+
+    _ => Err(DrasilDNSError::UnsupportedAlgorithm { algorithm: algorithm.into() }),
+  }
+}
+
+impl RRset {
+  /// Verifies an RRSIG record covering this RRset with a candidate DNSKEY record (RFC4035 section 5.3).
+  /// `now` is the current time in seconds since the UNIX epoch, as given by the caller's clock.
+  ///
+  /// The RRSIG must have the owner name, class and covered type of the RRset, be made by the zone containing it
+  /// (the signer name is an ancestor of the owner name and the owner name of the key), have no more labels than the
+  /// owner name and be valid at `now`. The key must be a zone key (protocol 3) matching the key tag and algorithm of the RRSIG.
+  /// Fails with `DrasilDNSError::InvalidSignature` naming the first failed check,
+  /// or with `DrasilDNSError::UnsupportedAlgorithm` if the algorithm can't be verified.
+  pub fn verify(&self, rrsig: &Record, dnskey: &Record, now: u64) -> Result<(), DrasilDNSError> {
+    let Record::RRSIG {
+      domain, type_covered, algorithm, labels, signature_expiration, signature_inception, key_tag, signer_name, signature, ..
+    } = rrsig else {
+      return Err(invalid(format!("expected an RRSIG record, found {}", rrsig.record_type())));
+    };
+    let (Record::DNSKEY { flags, protocol, algorithm: key_algorithm, public_key, .. }, Some(dnskey_tag)) = (dnskey, dnskey.key_tag()) else {
+      return Err(invalid(format!("expected a DNSKEY record, found {}", dnskey.record_type())));
+    };
+
+    if domain != self.name() || rrsig.class() != self.class() || RecordType::from(*type_covered) != self.record_type() {
+      return Err(invalid(format!(
+        "RRSIG {} {} covering {} doesn't cover RRset {} {} {}",
+        domain, rrsig.class(), RecordType::from(*type_covered), self.name(), self.class(), self.record_type(),
+      )));
+    }
+
+    if signer_name != dnskey.domain() || !self.name().is_subdomain_of(signer_name) {
+      return Err(invalid(format!("signer name {} doesn't match the key {} or the zone of {}", signer_name, dnskey.domain(), self.name())));
+    }
+
+    // the leading `*` label of wildcard owner names is not counted (RFC4034 section 3.1.3)
+    let count = self.name().label_count() - self.name().labels().next().is_some_and(|l| l == b"*") as usize;
+    if *labels as usize > count {
+      return Err(invalid(format!("RRSIG has more labels ({}) than the owner name {}", labels, self.name())));
+    }
+
+    if *key_tag != dnskey_tag {
+      return Err(invalid(format!("RRSIG key tag {} doesn't match the key tag {} of the DNSKEY", key_tag, dnskey_tag)));
+    }
+    if algorithm != key_algorithm {
+      return Err(invalid(format!("RRSIG algorithm {} doesn't match the DNSKEY algorithm {}", algorithm, key_algorithm)));
+    }
+    if flags & 0x0100 == 0 || *protocol != 3 {
+      return Err(invalid(format!("DNSKEY {} is not a zone key (protocol: {})", dnskey_tag, protocol)));
+    }
+
+    // timestamps are compared as serial numbers to the current time truncated to 32 bits
+    let now = now as u32;
+    if !serial_le(*signature_inception, now) {
+      return Err(invalid(format!("RRSIG is not valid before {}", signature_inception)));
+    }
+    if !serial_le(now, *signature_expiration) {
+      return Err(invalid(format!("RRSIG expired at {}", signature_expiration)));
+    }
+
+    verify_signature(*algorithm, public_key, &self.signed_data(rrsig)?, signature)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rrset(records: &[&str]) -> RRset {
+    RRset::new(records.iter().map(|r| r.parse().unwrap()).collect()).unwrap()
+  }
+
+  #[test]
+  fn verify_rrsig_examples() {
+    // examples of RFC5702 section 6.1, RFC6605 sections 6.1 and 6.2 and RFC8080 section 6.1
+    let examples = [
+      (
+        "example.net. 3600 IN DNSKEY 256 3 8 AwEAAcFcGsaxxdgiuuGmCkVImy4h99CqT7jwY3pexPGcnUFtR2Fh36BponcwtkZ4cAgtvd4Qs8PkxUdp6p/DlUmObdk=",
+        "www.example.net. 3600 IN A 192.0.2.91",
+        "www.example.net. 3600 IN RRSIG A 8 3 3600 20300101000000 20000101000000 9033 example.net. \
+          kRCOH6u7l0QGy9qpC9l1sLncJcOKFLJ7GhiUOibu4teYp5VE9RncriShZNz85mwlMgNEacFYK/lPtPiVYP4bwg==",
+        1_000_000_000,
+      ),
+      (
+        "example.net. 3600 IN DNSKEY 257 3 13 GojIhhXUN/u4v54ZQqGSnyhWJwaubCvTmeexv7bR6edbkrSqQpF64cYbcB7wNcP+e+MAnLr+Wi9xMWyQLc8NAA==",
+        "www.example.net. 3600 IN A 192.0.2.1",
+        "www.example.net. 3600 IN RRSIG A 13 3 3600 20100909100439 20100812100439 55648 example.net. \
+          qx6wLYqmh+l9oCKTN6qIc+bw6ya+KJ8oMz0YP107epXAyGmt+3SNruPFKG7tZoLBLlUzGGus7ZwmwWep666VCw==",
+        1_282_000_000,
+      ),
+      (
+        "example.net. 3600 IN DNSKEY 257 3 14 xKYaNhWdGOfJ+nPrL8/arkwf2EY3MDJ+SErKivBVSum1w/egsXvSADtNJhyem5RCOpgQ6K8X1DRSEkrbYQ+OB+v8/uX45NBwY8rp65F6Glur8I/mlVNgF6W/qTI37m40",
+        "www.example.net. 3600 IN A 192.0.2.1",
+        "www.example.net. 3600 IN RRSIG A 14 3 3600 20100909102025 20100812102025 10771 example.net. \
+          /L5hDKIvGDyI1fcARX3z65qrmPsVz73QD1Mr5CEqOiLP95hxQouuroGCeZOvzFaxsT8Glr74hbavRKayJNuydCuzWTSSPdz7wnqXL5bdcJzusdnI0RSMROxxwGipWcJm",
+        1_282_000_000,
+      ),
+      (
+        "example.com. 3600 IN DNSKEY 257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=",
+        "example.com. 3600 IN MX 10 mail.example.com.",
+        "example.com. 3600 IN RRSIG MX 15 2 3600 1440021600 1438207200 3613 example.com. \
+          oL9krJun7xfBOIWcGHi7mag5/hdZrKWw15jPGrHpjQeRAvTdszaPD+QLs3fx8A4M3e23mRZ9VrbpMngwcrqNAg==",
+        1_439_000_000,
+      ),
+    ];
+
+    for (dnskey, record, rrsig, now) in examples {
+      let (dnskey, rrsig): (Record, Record) = (dnskey.parse().unwrap(), rrsig.parse().unwrap());
+      let rrset = rrset(&[record]);
+      assert!(rrset.verify(&rrsig, &dnskey, now).is_ok(), "Failed to verify {}: {:?}", rrsig, rrset.verify(&rrsig, &dnskey, now));
+
+      let Record::RRSIG { signature_inception, signature_expiration, .. } = rrsig else { unreachable!() };
+      assert!(rrset.verify(&rrsig, &dnskey, signature_inception as u64 - 1).is_err(), "RRSIG accepted before inception");
+      assert!(rrset.verify(&rrsig, &dnskey, signature_expiration as u64 + 1).is_err(), "RRSIG accepted after expiration");
+
+      let mut tampered = rrsig.clone();
+      if let Record::RRSIG { signature, .. } = &mut tampered {
+        signature[10] ^= 1;
+      }
+      assert!(rrset.verify(&tampered, &dnskey, now).is_err(), "Tampered signature accepted");
+    }
+  }
+
+  #[test]
+  fn verify_rrsig_checks() {
+    let dnskey: Record = "example.com. 3600 IN DNSKEY 257 3 15 l02Woi0iS8Aa25FQkUd9RMzZHJpBoRQwAQEX1SxZJA4=".parse().unwrap();
+    let rrsig: Record = "example.com. 3600 IN RRSIG MX 15 2 3600 1440021600 1438207200 3613 example.com. \
+      oL9krJun7xfBOIWcGHi7mag5/hdZrKWw15jPGrHpjQeRAvTdszaPD+QLs3fx8A4M3e23mRZ9VrbpMngwcrqNAg==".parse().unwrap();
+    let mx = rrset(&["example.com. 3600 IN MX 10 mail.example.com."]);
+    let now = 1_439_000_000;
+
+    let changed = |f: fn(&mut Record)| {
+      let mut rrsig = rrsig.clone();
+      f(&mut rrsig);
+      rrsig
+    };
+    let rrsigs = [
+      (changed(|r| if let Record::RRSIG { key_tag, .. } = r { *key_tag = 3614 }), "key tag"),
+      (changed(|r| if let Record::RRSIG { algorithm, .. } = r { *algorithm = DNSSECAlgorithm::ED448 }), "algorithm"),
+      (changed(|r| if let Record::RRSIG { signer_name, .. } = r { *signer_name = "com".parse().unwrap() }), "signer name"),
+      (changed(|r| if let Record::RRSIG { labels, .. } = r { *labels = 3 }), "labels"),
+      (changed(|r| if let Record::RRSIG { type_covered, .. } = r { *type_covered = RecordType::A.into() }), "type covered"),
+    ];
+    for (rrsig, check) in rrsigs {
+      assert!(matches!(mx.verify(&rrsig, &dnskey, now), Err(DrasilDNSError::InvalidSignature { .. })), "Mismatched {} accepted", check);
+    }
+
+    let tampered = rrset(&["example.com. 3600 IN MX 20 mail.example.com."]);
+    assert!(tampered.verify(&rrsig, &dnskey, now).is_err(), "Tampered RRset accepted");
+    let not_a_key: Record = "example.com. 3600 IN A 192.0.2.1".parse().unwrap();
+    assert!(mx.verify(&rrsig, &not_a_key, now).is_err(), "Non-DNSKEY record accepted");
+
+    // timestamps wrap around, a signature made shortly before 2^32 seconds is still valid after it
+    assert!(serial_le(u32::MAX - 10, 5) && !serial_le(5, u32::MAX - 10), "Timestamps not compared as serial numbers");
+
+    assert!(matches!(
+      verify_signature(DNSSECAlgorithm::RSASHA1, &[1, 3, 1], b"data", b"signature"),
+      Err(DrasilDNSError::UnsupportedAlgorithm { algorithm: 5 }),
+    ), "Unsupported algorithm not reported");
+  }
+}
@@ -11,7 +11,11 @@ pub enum DrasilDNSError {
   #[error("too many jumps encountered in label sequence (max 5 allowed)")]
   TooManyJumpsInLabelSequence,
   #[error("label size exceeds 63 characters (size: {size})")]
-  LabelTooLarge { size: u8 },
+  LabelTooLarge { size: usize },
+  #[error("domain name size exceeds 255 octets (size: {size})")]
+  NameTooLarge { size: usize },
+  #[error("character-string size exceeds 255 octets (size: {size})")]
+  CharacterStringTooLarge { size: usize },
   #[error("reached the end while parsing the packet, please ensure packet data is correct")]
   EOF,
   #[error("invalid source netmask provided (family: {family}, max: {max}, provided: {provided})")]
@@ -22,6 +26,20 @@ pub enum DrasilDNSError {
   InvalidNetworkFamily { family: u16 },
   #[error("invalid EDNS(0) option length (option-type: {option_type}, size: {size})")]
   InvalidEDNSOptionLength { option_type: u16, size: u16 },
+  #[error("RDATA does not match RDLENGTH (type: {record_type}, size: {size})")]
+  InvalidRDataLength { record_type: u16, size: usize },
+  #[error("invalid SvcParam length (key: {key}, size: {size})")]
+  InvalidSvcParamLength { key: u16, size: u16 },
+  #[error("invalid SvcParams: {msg}")]
+  InvalidSvcParams { msg: String },
   #[error("invalid data: {msg}")]
   InvalidData { msg: String },
+  #[error("unsupported DNSSEC algorithm (value: {algorithm})")]
+  UnsupportedAlgorithm { algorithm: u8 },
+  #[error("RRSIG validation failed: {msg}")]
+  InvalidSignature { msg: String },
+  #[error("syntax error at line {line}, column {column}: {msg}")]
+  Syntax { line: usize, column: usize, msg: String },
+  #[error("I/O error: {0}")]
+  Io(#[from] std::io::Error),
 }
\ No newline at end of file
@@ -0,0 +1,20 @@
+// ===== Imports =====
+use std::collections::HashSet;
+use crate::{
+  buffer::Buffer,
+  error::DrasilDNSError,
+  record::edns::EDNSOption,
+  types::RecordType,
+};
+// ===================
+
+/// Parses a single EDNS option from the provided bytes
+pub fn parse_edns_option(data: &[u8]) -> Result<EDNSOption, DrasilDNSError> {
+  let mut buff: Buffer = data.into();
+  EDNSOption::parse(&mut buff)
+}
+
+/// Parses the type bitmaps of an NSEC or NSEC3 record from the provided bytes
+pub fn parse_type_bitmaps(data: &[u8]) -> Result<HashSet<RecordType>, DrasilDNSError> {
+  RecordType::parse_type_bitmaps(data.into())
+}
@@ -1,29 +1,97 @@
 
 // ===== Imports =====
+use std::fmt;
 use crate::{buffer::Buffer, error::DrasilDNSError};
 // ===================
 
 /// # Request Kind
 /// Flag in packet's header. Helps differentiate between queries and its responses.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RequestKind {
   Query = 0,
   Response = 1,
 }
 
-impl From<u8> for RequestKind {
+impl TryFrom<u8> for RequestKind {
+  type Error = DrasilDNSError;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    match value {
+      0 => Ok(RequestKind::Query),
+      1 => Ok(RequestKind::Response),
+      v => Err(DrasilDNSError::InvalidData { msg: format!("invalid value ({}) for RequestKind", v) }),
+    }
+  }
+}
+
+/// # Opcode
+/// Kind of query contained in the packet (RFC1035 section 4.1.1, IANA DNS OpCodes registry).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum Opcode {
+  QUERY = 0, // Standard query
+  IQUERY = 1, // Inverse query, obsoleted by RFC3425
+  STATUS = 2, // Server status request
+  NOTIFY = 4, // Zone change notification (RFC1996)
+  UPDATE = 5, // Dynamic update (RFC2136)
+  DSO = 6, // DNS Stateful Operations (RFC8490)
+  Unknown(u8),
+}
+
+impl From<Opcode> for u8 {
+  fn from(value: Opcode) -> Self {
+    match value {
+      Opcode::QUERY => 0,
+      Opcode::IQUERY => 1,
+      Opcode::STATUS => 2,
+      Opcode::NOTIFY => 4,
+      Opcode::UPDATE => 5,
+      Opcode::DSO => 6,
+      Opcode::Unknown(v) => v,
+    }
+  }
+}
+
+impl From<u8> for Opcode {
   fn from(value: u8) -> Self {
     match value {
-      0 => RequestKind::Query,
-      1 => RequestKind::Response,
-      _ => panic!("invalid value ({}) for RequestKind", value),
+      0 => Self::QUERY,
+      1 => Self::IQUERY,
+      2 => Self::STATUS,
+      4 => Self::NOTIFY,
+      5 => Self::UPDATE,
+      6 => Self::DSO,
+      v => Self::Unknown(v),
+    }
+  }
+}
+
+impl fmt::Display for Opcode {
+  /// Writes the mnemonic of the opcode, or `RESERVED<n>` for unassigned values (as dig does)
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::QUERY => f.write_str("QUERY"),
+      Self::IQUERY => f.write_str("IQUERY"),
+      Self::STATUS => f.write_str("STATUS"),
+      Self::NOTIFY => f.write_str("NOTIFY"),
+      Self::UPDATE => f.write_str("UPDATE"),
+      Self::DSO => f.write_str("DSO"),
+      Self::Unknown(v) => write!(f, "RESERVED{}", v),
     }
   }
 }
 
 /// # Response Code
-/// Flag representing packet's response.
+/// Flag representing packet's response (IANA DNS RCODEs registry).
+/// Only the lower 4 bits fit in the header, values above 15 need the `extended_rcode` of an OPT record (RFC6891).
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u16)]
 pub enum ResponseCode {
   NOERROR = 0,
   FORMERR = 1,
@@ -31,32 +99,126 @@ pub enum ResponseCode {
   NXDOMAIN = 3,
   NOTIMP = 4,
   REFUSED = 5,
+  YXDOMAIN = 6, // Name exists when it should not (RFC2136)
+  YXRRSET = 7, // RRset exists when it should not (RFC2136)
+  NXRRSET = 8, // RRset that should exist does not (RFC2136)
+  NOTAUTH = 9, // Server not authoritative for zone (RFC2136) or not authorized (RFC8945)
+  NOTZONE = 10, // Name not contained in zone (RFC2136)
+  DSOTYPENI = 11, // DSO-TYPE not implemented (RFC8490)
+  BADVERS = 16, // Bad OPT version (RFC6891), also BADSIG in TSIG records (RFC8945)
+  BADKEY = 17, // Key not recognized (RFC8945)
+  BADTIME = 18, // Signature out of time window (RFC8945)
+  BADMODE = 19, // Bad TKEY mode (RFC2930)
+  BADNAME = 20, // Duplicate key name (RFC2930)
+  BADALG = 21, // Algorithm not supported (RFC2930)
+  BADTRUNC = 22, // Bad truncation (RFC8945)
+  BADCOOKIE = 23, // Bad/missing server cookie (RFC7873)
+  Unknown(u16),
 }
 
-impl From<u8> for ResponseCode {
-  fn from(value: u8) -> Self {
+impl From<ResponseCode> for u16 {
+  fn from(value: ResponseCode) -> Self {
     match value {
+      ResponseCode::NOERROR => 0,
+      ResponseCode::FORMERR => 1,
+      ResponseCode::SERVFAIL => 2,
+      ResponseCode::NXDOMAIN => 3,
+      ResponseCode::NOTIMP => 4,
+      ResponseCode::REFUSED => 5,
+      ResponseCode::YXDOMAIN => 6,
+      ResponseCode::YXRRSET => 7,
+      ResponseCode::NXRRSET => 8,
+      ResponseCode::NOTAUTH => 9,
+      ResponseCode::NOTZONE => 10,
+      ResponseCode::DSOTYPENI => 11,
+      ResponseCode::BADVERS => 16,
+      ResponseCode::BADKEY => 17,
+      ResponseCode::BADTIME => 18,
+      ResponseCode::BADMODE => 19,
+      ResponseCode::BADNAME => 20,
+      ResponseCode::BADALG => 21,
+      ResponseCode::BADTRUNC => 22,
+      ResponseCode::BADCOOKIE => 23,
+      ResponseCode::Unknown(v) => v,
+    }
+  }
+}
+
+impl From<u16> for ResponseCode {
+  fn from(value: u16) -> Self {
+    match value {
+      0 => ResponseCode::NOERROR,
       1 => ResponseCode::FORMERR,
       2 => ResponseCode::SERVFAIL,
       3 => ResponseCode::NXDOMAIN,
       4 => ResponseCode::NOTIMP,
       5 => ResponseCode::REFUSED,
-      0 | _ => ResponseCode::NOERROR,
+      6 => ResponseCode::YXDOMAIN,
+      7 => ResponseCode::YXRRSET,
+      8 => ResponseCode::NXRRSET,
+      9 => ResponseCode::NOTAUTH,
+      10 => ResponseCode::NOTZONE,
+      11 => ResponseCode::DSOTYPENI,
+      16 => ResponseCode::BADVERS,
+      17 => ResponseCode::BADKEY,
+      18 => ResponseCode::BADTIME,
+      19 => ResponseCode::BADMODE,
+      20 => ResponseCode::BADNAME,
+      21 => ResponseCode::BADALG,
+      22 => ResponseCode::BADTRUNC,
+      23 => ResponseCode::BADCOOKIE,
+      v => ResponseCode::Unknown(v),
     }
   }
 }
 
+impl fmt::Display for ResponseCode {
+  /// Writes the mnemonic of the response code, or `RESERVED<n>` for unassigned values (as dig does)
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mnemonic = match self {
+      Self::NOERROR => "NOERROR",
+      Self::FORMERR => "FORMERR",
+      Self::SERVFAIL => "SERVFAIL",
+      Self::NXDOMAIN => "NXDOMAIN",
+      Self::NOTIMP => "NOTIMP",
+      Self::REFUSED => "REFUSED",
+      Self::YXDOMAIN => "YXDOMAIN",
+      Self::YXRRSET => "YXRRSET",
+      Self::NXRRSET => "NXRRSET",
+      Self::NOTAUTH => "NOTAUTH",
+      Self::NOTZONE => "NOTZONE",
+      Self::DSOTYPENI => "DSOTYPENI",
+      Self::BADVERS => "BADVERS",
+      Self::BADKEY => "BADKEY",
+      Self::BADTIME => "BADTIME",
+      Self::BADMODE => "BADMODE",
+      Self::BADNAME => "BADNAME",
+      Self::BADALG => "BADALG",
+      Self::BADTRUNC => "BADTRUNC",
+      Self::BADCOOKIE => "BADCOOKIE",
+      Self::Unknown(v) => return write!(f, "RESERVED{}", v),
+    };
+    f.write_str(mnemonic)
+  }
+}
+
 /// # Header
 /// Struct representing DNS packet header.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
   pub id: u16,
   pub request_kind: RequestKind,
-  pub opcode: u8,
+  pub opcode: Opcode,
   pub is_authoritative_answer: bool,
   pub is_truncated_message: bool,
   pub is_recursion_desired: bool,
   pub is_recursion_available: bool,
+  pub z: bool, // reserved, must be zero (RFC1035) but kept as received
+  pub authentic_data: bool, // AD flag (RFC4035 section 3.2.3)
+  pub checking_disabled: bool, // CD flag (RFC4035 section 3.2.2)
+  /// Lower 4 bits of the response code, see `Packet::response_code` for the full value
   pub response_code: ResponseCode,
   pub question_count: u16,
   pub answer_count: u16,
@@ -74,16 +236,18 @@ impl Header {
       let authority_count = buff.read_u16()?;
       let additional_count = buff.read_u16()?;
 
-      let request_kind = RequestKind::from(flag_high >> 7);
-      let opcode = (flag_high & 0b01111000) >> 3;
+      let request_kind = RequestKind::try_from(flag_high >> 7)?;
+      let opcode = Opcode::from((flag_high & 0b01111000) >> 3);
 
       let is_authoritative_answer = ((flag_high & 0b00000100) >> 2) == 1;
       let is_truncated_message = ((flag_high & 0b00000010) >> 1) == 1;
       let is_recursion_desired = (flag_high & 0b00000001) == 1;
       let is_recursion_available = (flag_low >> 7) == 1;
 
-      let _ = (flag_low & 0b01110000) >> 4; // reserved section
-      let response_code = ResponseCode::from(flag_low & 0b00001111);
+      let z = (flag_low & 0b01000000) != 0;
+      let authentic_data = (flag_low & 0b00100000) != 0;
+      let checking_disabled = (flag_low & 0b00010000) != 0;
+      let response_code = ResponseCode::from((flag_low & 0b00001111) as u16);
 
       Ok(Self {
         id,
@@ -93,6 +257,9 @@ impl Header {
         is_truncated_message,
         is_recursion_desired,
         is_recursion_available,
+        z,
+        authentic_data,
+        checking_disabled,
         response_code,
         question_count,
         answer_count,
@@ -103,13 +270,18 @@ impl Header {
   }
 
   pub(crate) fn write_bytes(&self, buff: &mut Buffer) -> Result<(), DrasilDNSError> {
+    let opcode = u8::from(self.opcode);
+    if opcode > 0x0F {
+      return Err(DrasilDNSError::InvalidData { msg: format!("opcode {} doesn't fit in 4 bits", opcode) });
+    }
+
     let mut b = Buffer::with_capacity(12);
 
     let mut flag_high = 0_u8;
     let mut flag_low = 0_u8;
 
     flag_high |= (self.request_kind as u8) << 7;
-    flag_high |= (self.opcode as u8) << 3;
+    flag_high |= opcode << 3;
 
     if self.is_authoritative_answer {
       flag_high |= 0b00000100;
@@ -127,7 +299,19 @@ impl Header {
       flag_low |= 0b10000000;
     }
 
-    flag_low |= self.response_code as u8;
+    if self.z {
+      flag_low |= 0b01000000;
+    }
+
+    if self.authentic_data {
+      flag_low |= 0b00100000;
+    }
+
+    if self.checking_disabled {
+      flag_low |= 0b00010000;
+    }
+
+    flag_low |= (u16::from(self.response_code) & 0b00001111) as u8;
 
     b.write_u16(self.id)?;
     b.write_u16(u16::from_be_bytes([flag_high, flag_low]))?;
@@ -150,12 +334,15 @@ mod tests {
     let header = Header {
       id: 100,
       request_kind: RequestKind::Query,
-      opcode: 10,
+      opcode: Opcode::Unknown(10),
       is_authoritative_answer: false,
       is_truncated_message: false,
       is_recursion_desired: true,
       is_recursion_available: false,
-      response_code: ResponseCode::NOERROR,
+      z: false,
+      authentic_data: true,
+      checking_disabled: true,
+      response_code: ResponseCode::NXDOMAIN,
       question_count: 1,
       answer_count: 0,
       authority_count: 0,
@@ -170,5 +357,8 @@ mod tests {
       .expect("Failed at header read");
 
     assert_eq!(header, header_after_write, "Header is not equal after write+read");
+
+    let header = Header { opcode: Opcode::Unknown(16), ..header };
+    header.write_bytes(&mut Buffer::with_capacity(12)).expect_err("Opcode larger than 4 bits accepted");
   }
 }
\ No newline at end of file
@@ -0,0 +1,314 @@
+
+/// Provides the `application/dns-json` format of DoH JSON APIs
+pub mod doh;
+
+// ===== Imports =====
+use serde_json::{Map, Value};
+use crate::{
+  error::DrasilDNSError,
+  header::{Header, RequestKind},
+  name::Name,
+  packet::Packet,
+  presentation,
+  question::Question,
+  record::Record,
+  types::{RecordClass, RecordType},
+};
+// ===================
+
+fn invalid(msg: String) -> DrasilDNSError {
+  DrasilDNSError::InvalidData { msg }
+}
+
+/// Reads an optional integer member, failing if it has another type or doesn't fit in `T`
+fn get_int<T: TryFrom<u64>>(obj: &Map<String, Value>, key: &str) -> Result<Option<T>, DrasilDNSError> {
+  match obj.get(key) {
+    None => Ok(None),
+    Some(value) => value.as_u64()
+      .and_then(|v| T::try_from(v).ok())
+      .map(Some)
+      .ok_or_else(|| invalid(format!("invalid value for member \"{}\": {}", key, value))),
+  }
+}
+
+fn get_bool(obj: &Map<String, Value>, key: &str) -> Result<bool, DrasilDNSError> {
+  match obj.get(key) {
+    None => Ok(false),
+    Some(value) => value.as_bool().ok_or_else(|| invalid(format!("invalid value for member \"{}\": {}", key, value))),
+  }
+}
+
+fn get_str<'a>(obj: &'a Map<String, Value>, key: &str) -> Result<Option<&'a str>, DrasilDNSError> {
+  match obj.get(key) {
+    None => Ok(None),
+    Some(value) => value.as_str().map(Some).ok_or_else(|| invalid(format!("invalid value for member \"{}\": {}", key, value))),
+  }
+}
+
+/// Reads the type of an RR or question object, given as `TYPE` (or `QTYPE`) or else by its mnemonic in `TYPEname`
+fn get_type(obj: &Map<String, Value>, prefix: &str) -> Result<RecordType, DrasilDNSError> {
+  if let Some(v) = get_int::<u16>(obj, &format!("{}TYPE", prefix))? {
+    return Ok(v.into());
+  }
+
+  match get_str(obj, &format!("{}TYPEname", prefix))? {
+    Some(name) => name.parse(),
+    None => Err(invalid(format!("missing member \"{}TYPE\"", prefix))),
+  }
+}
+
+/// Reads the class of an RR or question object, given as `CLASS` (or `QCLASS`) or `CLASSname`, defaulting to `IN`
+fn get_class(obj: &Map<String, Value>, prefix: &str) -> Result<RecordClass, DrasilDNSError> {
+  if let Some(v) = get_int::<u16>(obj, &format!("{}CLASS", prefix))? {
+    return Ok(v.into());
+  }
+
+  match get_str(obj, &format!("{}CLASSname", prefix))? {
+    Some(name) => name.parse(),
+    None => Ok(RecordClass::IN),
+  }
+}
+
+fn get_name(obj: &Map<String, Value>, key: &str) -> Result<Name, DrasilDNSError> {
+  get_str(obj, key)?.ok_or_else(|| invalid(format!("missing member \"{}\"", key)))?.parse()
+}
+
+fn as_object<'a>(value: &'a Value, what: &str) -> Result<&'a Map<String, Value>, DrasilDNSError> {
+  value.as_object().ok_or_else(|| invalid(format!("{} is not an object", what)))
+}
+
+fn question_to_json(q: &Question, prefix: &str, obj: &mut Map<String, Value>) {
+  let name_key = if prefix.is_empty() { "NAME".to_string() } else { format!("{}NAME", prefix) };
+  obj.insert(name_key, q.name.to_string().into());
+  obj.insert(format!("{}TYPE", prefix), u16::from(q.record_type).into());
+  obj.insert(format!("{}TYPEname", prefix), q.record_type.to_string().into());
+  obj.insert(format!("{}CLASS", prefix), u16::from(q.record_class).into());
+  obj.insert(format!("{}CLASSname", prefix), q.record_class.to_string().into());
+}
+
+/// Encodes a record as an RR object (RFC8427 section 2.2). The RDATA is always given as `RDATAHEX`,
+/// types with a presentation format of their own also get an `rdata<TYPE>` member.
+fn record_to_json(record: &Record) -> Result<Value, DrasilDNSError> {
+  let rdata = record.rdata()?;
+  let record_type = record.record_type();
+  let mut obj = Map::new();
+
+  obj.insert("NAME".into(), record.domain().to_string().into());
+  obj.insert("TYPE".into(), u16::from(record_type).into());
+  obj.insert("TYPEname".into(), record_type.to_string().into());
+  obj.insert("CLASS".into(), u16::from(record.class()).into());
+  // the class of OPT records holds the UDP payload size
+  if !matches!(record, Record::OPT { .. }) {
+    obj.insert("CLASSname".into(), record.class().to_string().into());
+  }
+  obj.insert("TTL".into(), record.ttl().into());
+  obj.insert("RDLENGTH".into(), rdata.len().into());
+  obj.insert("RDATAHEX".into(), presentation::fmt_hex(&rdata).into());
+
+  if !matches!(record, Record::OPT { .. } | Record::NULL { .. } | Record::Unknown { .. }) {
+    obj.insert(format!("rdata{}", record_type), record.rdata_to_string()?.into());
+  }
+
+  Ok(Value::Object(obj))
+}
+
+/// Decodes an RR object, using `RDATAHEX` if present and else the `rdata<TYPE>` member
+fn record_from_json(value: &Value) -> Result<Record, DrasilDNSError> {
+  let obj = as_object(value, "RR")?;
+
+  let domain = get_name(obj, "NAME")?;
+  let record_type = get_type(obj, "")?;
+  let class = get_class(obj, "")?;
+  let ttl = get_int(obj, "TTL")?.unwrap_or(0);
+
+  if let Some(hex) = get_str(obj, "RDATAHEX")? {
+    let data = presentation::parse_hex(hex)?;
+    if get_int::<usize>(obj, "RDLENGTH")?.is_some_and(|len| len != data.len()) {
+      return Err(DrasilDNSError::InvalidRDataLength { record_type: record_type.into(), size: data.len() });
+    }
+    return Record::from_generic_rdata(domain, ttl, class, record_type, data);
+  }
+
+  match get_str(obj, &format!("rdata{}", record_type))? {
+    Some(rdata) => Record::from_rdata_str(domain, ttl, class, record_type, rdata),
+    None => Err(invalid(format!("RR {} {} has neither RDATAHEX nor rdata{}", domain, record_type, record_type))),
+  }
+}
+
+fn section_from_json(obj: &Map<String, Value>, key: &str) -> Result<Vec<Record>, DrasilDNSError> {
+  match obj.get(key) {
+    None => Ok(vec![]),
+    Some(Value::Array(rrs)) => rrs.iter().map(record_from_json).collect(),
+    Some(_) => Err(invalid(format!("member \"{}\" is not an array", key))),
+  }
+}
+
+impl Packet {
+  /// Encodes the packet as a message object of the JSON format of RFC8427, using the standard member names
+  /// (`ID`, `QR`, `Opcode`, `QDCOUNT`, `answerRRs`, `rdataA`, ...).
+  /// A single question is given with the `QNAME`, `QTYPE` and `QCLASS` members, several ones in `questionRRs`.
+  pub fn to_rfc8427(&self) -> Result<Value, DrasilDNSError> {
+    let h = &self.header;
+    let mut obj = Map::new();
+
+    obj.insert("ID".into(), h.id.into());
+    obj.insert("QR".into(), (h.request_kind == RequestKind::Response).into());
+    obj.insert("Opcode".into(), u8::from(h.opcode).into());
+    obj.insert("AA".into(), h.is_authoritative_answer.into());
+    obj.insert("TC".into(), h.is_truncated_message.into());
+    obj.insert("RD".into(), h.is_recursion_desired.into());
+    obj.insert("RA".into(), h.is_recursion_available.into());
+    obj.insert("AD".into(), h.authentic_data.into());
+    obj.insert("CD".into(), h.checking_disabled.into());
+    obj.insert("RCODE".into(), u16::from(h.response_code).into());
+    obj.insert("QDCOUNT".into(), h.question_count.into());
+    obj.insert("ANCOUNT".into(), h.answer_count.into());
+    obj.insert("NSCOUNT".into(), h.authority_count.into());
+    obj.insert("ARCOUNT".into(), h.additional_count.into());
+
+    match self.questions.as_slice() {
+      [] => {},
+      [q] => question_to_json(q, "Q", &mut obj),
+      questions => {
+        let questions = questions.iter().map(|q| {
+          let mut obj = Map::new();
+          question_to_json(q, "", &mut obj);
+          Value::Object(obj)
+        });
+        obj.insert("questionRRs".into(), questions.collect());
+      },
+    }
+
+    let sections = [("answerRRs", &self.answers), ("authorityRRs", &self.authority), ("additionalRRs", &self.additional)];
+    for (key, records) in sections {
+      if !records.is_empty() {
+        obj.insert(key.into(), records.iter().map(record_to_json).collect::<Result<_, _>>()?);
+      }
+    }
+
+    Ok(Value::Object(obj))
+  }
+
+  /// Decodes a message object of the JSON format of RFC8427.
+  /// Missing header members default to zero (or false), missing counts to the length of their section.
+  /// RRs may give their RDATA as `RDATAHEX` or in presentation format as `rdata<TYPE>`.
+  pub fn from_rfc8427(value: &Value) -> Result<Self, DrasilDNSError> {
+    let obj = as_object(value, "message")?;
+
+    let questions = match (obj.get("questionRRs"), obj.get("QNAME")) {
+      (Some(Value::Array(questions)), _) => questions.iter()
+        .map(|q| {
+          let q = as_object(q, "question")?;
+          Ok(Question { name: get_name(q, "NAME")?, record_type: get_type(q, "")?, record_class: get_class(q, "")? })
+        })
+        .collect::<Result<_, DrasilDNSError>>()?,
+      (Some(_), _) => return Err(invalid("member \"questionRRs\" is not an array".to_string())),
+      (None, Some(_)) => vec![Question { name: get_name(obj, "QNAME")?, record_type: get_type(obj, "Q")?, record_class: get_class(obj, "Q")? }],
+      (None, None) => vec![],
+    };
+
+    let answers = section_from_json(obj, "answerRRs")?;
+    let authority = section_from_json(obj, "authorityRRs")?;
+    let additional = section_from_json(obj, "additionalRRs")?;
+
+    let count = |key: &str, len: usize| -> Result<u16, DrasilDNSError> {
+      get_int(obj, key)?.map_or_else(|| u16::try_from(len).map_err(|_| invalid(format!("too many entries for {}", key))), Ok)
+    };
+
+    let header = Header {
+      id: get_int(obj, "ID")?.unwrap_or(0),
+      request_kind: if get_bool(obj, "QR")? { RequestKind::Response } else { RequestKind::Query },
+      opcode: get_int::<u8>(obj, "Opcode")?.unwrap_or(0).into(),
+      is_authoritative_answer: get_bool(obj, "AA")?,
+      is_truncated_message: get_bool(obj, "TC")?,
+      is_recursion_desired: get_bool(obj, "RD")?,
+      is_recursion_available: get_bool(obj, "RA")?,
+      z: false,
+      authentic_data: get_bool(obj, "AD")?,
+      checking_disabled: get_bool(obj, "CD")?,
+      response_code: get_int::<u16>(obj, "RCODE")?.unwrap_or(0).into(),
+      question_count: count("QDCOUNT", questions.len())?,
+      answer_count: count("ANCOUNT", answers.len())?,
+      authority_count: count("NSCOUNT", authority.len())?,
+      additional_count: count("ARCOUNT", additional.len())?,
+    };
+
+    Ok(Self { header, questions, answers, authority, additional })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+  use crate::{packet::builder::PacketBuilder, record::edns::EDNSOption};
+  use super::*;
+
+  #[test]
+  fn packet_rfc8427() {
+    let packet = PacketBuilder::new(3)
+      .with_request_kind(RequestKind::Response)
+      .recursion_desired()
+      .add_question("example.com. A".parse().unwrap())
+      .add_answer("example.com. 300 IN A 192.0.2.1".parse().unwrap())
+      .add_answer("example.com. 300 IN TYPE65280 \\# 2 abcd".parse().unwrap())
+      .add_additional(Record::OPT {
+        udp_payload_size: 1232,
+        extended_rcode: 0,
+        version: 0,
+        dnssec_ok: true,
+        options: vec![EDNSOption::Cookie { client: 1, server: None }],
+      })
+      .build().expect("Failed to build packet");
+
+    let value = packet.to_rfc8427().expect("Failed to encode packet");
+    assert_eq!(value, json!({
+      "ID": 3, "QR": true, "Opcode": 0, "AA": false, "TC": false, "RD": true, "RA": false, "AD": false, "CD": false, "RCODE": 0,
+      "QDCOUNT": 1, "ANCOUNT": 2, "NSCOUNT": 0, "ARCOUNT": 1,
+      "QNAME": "example.com.", "QTYPE": 1, "QTYPEname": "A", "QCLASS": 1, "QCLASSname": "IN",
+      "answerRRs": [
+        {
+          "NAME": "example.com.", "TYPE": 1, "TYPEname": "A", "CLASS": 1, "CLASSname": "IN", "TTL": 300,
+          "RDLENGTH": 4, "RDATAHEX": "C0000201", "rdataA": "192.0.2.1",
+        },
+        {
+          "NAME": "example.com.", "TYPE": 65280, "TYPEname": "TYPE65280", "CLASS": 1, "CLASSname": "IN", "TTL": 300,
+          "RDLENGTH": 2, "RDATAHEX": "ABCD",
+        },
+      ],
+      "additionalRRs": [
+        { "NAME": ".", "TYPE": 41, "TYPEname": "OPT", "CLASS": 1232, "TTL": 32768, "RDLENGTH": 12, "RDATAHEX": "000A00080000000000000001" },
+      ],
+    }), "Packet not encoded correctly");
+    assert_eq!(Packet::from_rfc8427(&value).expect("Failed to decode packet"), packet, "Packet not equal after encode+decode");
+
+    // messages written by other tools may leave out members and give the RDATA only in presentation format
+    let packet = Packet::from_rfc8427(&json!({
+      "ID": 7,
+      "questionRRs": [{ "NAME": "example.com", "TYPEname": "MX" }],
+      "answerRRs": [{ "NAME": "example.com.", "TYPE": 15, "TTL": 60, "rdataMX": "10 mail.example.com." }],
+    })).expect("Failed to decode minimal message");
+    assert_eq!(packet.header.answer_count, 1, "Missing count not taken from section");
+    assert_eq!(packet.questions[0], "example.com. IN MX".parse().unwrap());
+    assert_eq!(packet.answers[0], "example.com. 60 IN MX 10 mail.example.com.".parse().unwrap());
+
+    assert!(Packet::from_rfc8427(&json!({ "answerRRs": [{ "NAME": "a.", "TYPE": 1 }] })).is_err(), "RR without RDATA accepted");
+    assert!(Packet::from_rfc8427(&json!({ "ID": 70000 })).is_err(), "Out of range ID accepted");
+    assert!(Packet::from_rfc8427(&json!({
+      "answerRRs": [{ "NAME": "a.", "TYPE": 1, "RDLENGTH": 5, "RDATAHEX": "C0000201" }],
+    })).is_err(), "Mismatching RDLENGTH accepted");
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn packet_serde() {
+    let packet = PacketBuilder::new(3)
+      .add_question("example.com. HTTPS".parse().unwrap())
+      .add_answer("example.com. 300 IN HTTPS 1 . alpn=h2,h3 ipv4hint=192.0.2.1".parse().unwrap())
+      .add_answer("example.com. 300 IN NSEC host.example.com. A MX RRSIG".parse().unwrap())
+      .build().expect("Failed to build packet");
+
+    let text = serde_json::to_string(&packet).expect("Failed to serialize packet");
+    let deserialized: Packet = serde_json::from_str(&text).expect("Failed to deserialize packet");
+    assert_eq!(deserialized, packet, "Packet not equal after serialize+deserialize");
+  }
+}
@@ -0,0 +1,171 @@
+
+// ===== Imports =====
+use serde_json::{Map, Value};
+use crate::{
+  error::DrasilDNSError,
+  header::{Header, Opcode, RequestKind, ResponseCode},
+  json::{as_object, get_bool, get_int, get_name, get_str, invalid},
+  packet::Packet,
+  question::Question,
+  record::Record,
+  types::{RecordClass, RecordType},
+};
+// ===================
+
+/// Encodes a record as `{"name", "type", "TTL", "data"}`, with the RDATA in presentation format
+fn record_to_json(record: &Record) -> Result<Value, DrasilDNSError> {
+  let mut obj = Map::new();
+  obj.insert("name".into(), record.domain().to_string().into());
+  obj.insert("type".into(), u16::from(record.record_type()).into());
+  obj.insert("TTL".into(), record.ttl().into());
+  obj.insert("data".into(), record.rdata_to_string()?.into());
+  Ok(Value::Object(obj))
+}
+
+/// Decodes a record object, its class is always `IN` as the format doesn't carry classes
+fn record_from_json(value: &Value) -> Result<Record, DrasilDNSError> {
+  let obj = as_object(value, "record")?;
+
+  let domain = get_name(obj, "name")?;
+  let record_type: RecordType = get_int::<u16>(obj, "type")?.ok_or_else(|| invalid("missing member \"type\"".to_string()))?.into();
+  let ttl = get_int(obj, "TTL")?.unwrap_or(0);
+  let data = get_str(obj, "data")?.ok_or_else(|| invalid("missing member \"data\"".to_string()))?;
+
+  Record::from_rdata_str(domain, ttl, RecordClass::IN, record_type, data)
+}
+
+fn section_from_json(obj: &Map<String, Value>, key: &str) -> Result<Vec<Record>, DrasilDNSError> {
+  match obj.get(key) {
+    None => Ok(vec![]),
+    Some(Value::Array(records)) => records.iter().map(record_from_json).collect(),
+    Some(_) => Err(invalid(format!("member \"{}\" is not an array", key))),
+  }
+}
+
+impl Packet {
+  /// Encodes the packet in the `application/dns-json` format of the Google and Cloudflare DoH JSON APIs:
+  /// `Status` (the full response code), the `TC`, `RD`, `RA`, `AD` and `CD` flags and the `Question`, `Answer`,
+  /// `Authority` and `Additional` arrays, whose records carry their RDATA in presentation format as `data`.
+  /// The format has no place for the ID, opcode or classes, and OPT records are left out.
+  pub fn to_doh_json(&self) -> Result<Value, DrasilDNSError> {
+    let h = &self.header;
+    let mut obj = Map::new();
+
+    obj.insert("Status".into(), u16::from(self.response_code()).into());
+    obj.insert("TC".into(), h.is_truncated_message.into());
+    obj.insert("RD".into(), h.is_recursion_desired.into());
+    obj.insert("RA".into(), h.is_recursion_available.into());
+    obj.insert("AD".into(), h.authentic_data.into());
+    obj.insert("CD".into(), h.checking_disabled.into());
+
+    let questions = self.questions.iter().map(|q| {
+      let mut obj = Map::new();
+      obj.insert("name".into(), q.name.to_string().into());
+      obj.insert("type".into(), u16::from(q.record_type).into());
+      Value::Object(obj)
+    });
+    obj.insert("Question".into(), questions.collect());
+
+    let sections = [("Answer", &self.answers), ("Authority", &self.authority), ("Additional", &self.additional)];
+    for (key, records) in sections {
+      let records: Vec<&Record> = records.iter().filter(|r| !matches!(r, Record::OPT { .. })).collect();
+      if !records.is_empty() {
+        obj.insert(key.into(), records.into_iter().map(record_to_json).collect::<Result<_, _>>()?);
+      }
+    }
+
+    Ok(Value::Object(obj))
+  }
+
+  /// Decodes a response in the `application/dns-json` format, see `to_doh_json`.
+  /// The packet is a response to a standard query with ID 0, its questions and records are of class `IN`.
+  /// Fails if `Status` needs the extended bits of an OPT record, which the format can't carry.
+  pub fn from_doh_json(value: &Value) -> Result<Self, DrasilDNSError> {
+    let obj = as_object(value, "response")?;
+
+    let questions = match obj.get("Question") {
+      None => vec![],
+      Some(Value::Array(questions)) => questions.iter()
+        .map(|q| {
+          let q = as_object(q, "question")?;
+          let record_type = get_int::<u16>(q, "type")?.ok_or_else(|| invalid("missing member \"type\"".to_string()))?;
+          Ok(Question { name: get_name(q, "name")?, record_type: record_type.into(), record_class: RecordClass::IN })
+        })
+        .collect::<Result<_, DrasilDNSError>>()?,
+      Some(_) => return Err(invalid("member \"Question\" is not an array".to_string())),
+    };
+
+    let answers = section_from_json(obj, "Answer")?;
+    let authority = section_from_json(obj, "Authority")?;
+    let additional = section_from_json(obj, "Additional")?;
+
+    let count = |key: &str, len: usize| u16::try_from(len).map_err(|_| invalid(format!("too many entries in {}", key)));
+
+    let header = Header {
+      id: 0,
+      request_kind: RequestKind::Response,
+      opcode: Opcode::QUERY,
+      is_authoritative_answer: false,
+      is_truncated_message: get_bool(obj, "TC")?,
+      is_recursion_desired: get_bool(obj, "RD")?,
+      is_recursion_available: get_bool(obj, "RA")?,
+      z: false,
+      authentic_data: get_bool(obj, "AD")?,
+      checking_disabled: get_bool(obj, "CD")?,
+      response_code: ResponseCode::NOERROR,
+      question_count: count("Question", questions.len())?,
+      answer_count: count("Answer", answers.len())?,
+      authority_count: count("Authority", authority.len())?,
+      additional_count: count("Additional", additional.len())?,
+    };
+
+    let mut packet = Self { header, questions, answers, authority, additional };
+    packet.set_response_code(get_int::<u16>(obj, "Status")?.unwrap_or(0).into())?;
+    Ok(packet)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+  use crate::packet::builder::PacketBuilder;
+  use super::*;
+
+  #[test]
+  fn packet_doh_json() {
+    let packet = PacketBuilder::new(0)
+      .with_request_kind(RequestKind::Response)
+      .recursion_desired()
+      .recursion_available()
+      .authentic_data()
+      .add_question("example.com. TXT".parse().unwrap())
+      .add_answer("example.com. 300 IN TXT \"v=spf1 -all\" \"a\\\"b\"".parse().unwrap())
+      .add_authority("example.com. 3600 IN SOA ns1.example.com. hostmaster.example.com. 1 7200 3600 1209600 300".parse().unwrap())
+      .build().expect("Failed to build packet");
+
+    let value = packet.to_doh_json().expect("Failed to encode packet");
+    assert_eq!(value, json!({
+      "Status": 0, "TC": false, "RD": true, "RA": true, "AD": true, "CD": false,
+      "Question": [{ "name": "example.com.", "type": 16 }],
+      "Answer": [{ "name": "example.com.", "type": 16, "TTL": 300, "data": "\"v=spf1 -all\" \"a\\\"b\"" }],
+      "Authority": [{
+        "name": "example.com.", "type": 6, "TTL": 3600,
+        "data": "ns1.example.com. hostmaster.example.com. 1 7200 3600 1209600 300",
+      }],
+    }), "Packet not encoded correctly");
+    assert_eq!(Packet::from_doh_json(&value).expect("Failed to decode packet"), packet, "Packet not equal after encode+decode");
+
+    // a response of a public resolver
+    let packet = Packet::from_doh_json(&json!({
+      "Status": 3, "TC": false, "RD": true, "RA": true, "AD": false, "CD": false,
+      "Question": [{ "name": "missing.example.com.", "type": 1 }],
+      "Authority": [{ "name": "example.com.", "type": 6, "TTL": 1800, "data": "ns.icann.org. noc.dns.icann.org. 2024 7200 3600 1209600 3600" }],
+      "Comment": "Response from 199.43.135.53.",
+    })).expect("Failed to decode response");
+    assert_eq!(packet.response_code(), ResponseCode::NXDOMAIN, "Status not decoded correctly");
+    assert_eq!(packet.header.authority_count, 1, "Counts not taken from sections");
+
+    assert!(Packet::from_doh_json(&json!({ "Status": 16 })).is_err(), "Extended status without OPT record accepted");
+    assert!(Packet::from_doh_json(&json!({ "Answer": [{ "name": "a.", "type": 1, "data": "300.0.0.1" }] })).is_err(), "Invalid data accepted");
+  }
+}
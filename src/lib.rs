@@ -1,10 +1,14 @@
 
 pub(crate) mod buffer;
+pub(crate) mod presentation;
 pub mod types;
 
 /// Provides error type for the crate
 pub mod error;
 
+/// Provides the `Name` type
+pub mod name;
+
 /// Provides the `Header` struct
 pub mod header;
 
@@ -17,25 +21,55 @@ pub mod record;
 /// Provides the `Packet` struct
 pub mod packet;
 
+/// Provides DNSSEC computations on records (key tags, DS digests, canonical form, NSEC3 hashes), verification and signing
+pub mod dnssec;
+
+/// Provides the master file (zone file) parser and writer
+pub mod zone;
+
+/// Provides the JSON representation of DNS messages (RFC8427)
+#[cfg(any(test, feature = "serde"))]
+pub mod json;
+
+/// Provides proptest strategies for the packet types.
+/// They only generate values in the form produced by the parsers (length fields matching their data,
+/// known codes mapped to their variants, etc.) so that every value survives a write+parse round trip unchanged.
+#[cfg(any(test, feature = "proptest"))]
+pub mod strategies;
+
+/// Entry points for the fuzz targets in `fuzz/`, not part of the public API
+#[cfg(fuzzing)]
+#[doc(hidden)]
+pub mod fuzzing;
+
 pub use crate::{
   error::DrasilDNSError,
+  name::Name,
   types::{
     RecordType,
     RecordClass,
     dnssec::{DNSSECAlgorithm, DNSSECDigestType},
+    dane::{TLSACertUsage, TLSASelector, TLSAMatchingType, SSHFPAlgorithm, SSHFPFingerprintType},
   },
   packet::{
     Packet,
     builder::PacketBuilder,
+    view::PacketRef,
   },
   header::{
     Header,
+    Opcode,
     RequestKind,
     ResponseCode,
   },
   question::Question,
+  zone::{ZoneParser, write_zone},
   record::{
     Record,
     edns::{EDNSOption, EDNSOptionType},
+    svcb::{SvcParam, SvcParamKey},
+    caa::{CAAValue, CAAIssuer},
+    generic::GenericRData,
+    rrset::RRset,
   },
 };
\ No newline at end of file
@@ -0,0 +1,313 @@
+// ===== Imports =====
+use std::{cmp::Ordering, fmt, hash::{Hash, Hasher}, str::FromStr};
+use crate::error::DrasilDNSError;
+// ===================
+
+/// Maximum size of a single label (in octets)
+pub const MAX_LABEL_LENGTH: usize = 63;
+
+/// Maximum size of a whole name in wire format (in octets), including the length octets and the root label
+pub const MAX_NAME_LENGTH: usize = 255;
+
+/// # Name
+/// A fully qualified domain name, stored as the raw labels found on the wire (root label excluded).
+///
+/// Labels are kept exactly as they were read, including their case and any non-ASCII octets.
+/// Comparisons (`Eq`, `Hash`, `Ord`) are however case-insensitive for ASCII letters as required by RFC4343,
+/// and ordering follows the canonical DNS name order of RFC4034 (section 6.1).
+#[derive(Clone, Default)]
+pub struct Name {
+  labels: Vec<Vec<u8>>,
+}
+
+impl Name {
+  /// Returns the root name (`.`)
+  pub const fn root() -> Self {
+    Self { labels: Vec::new() }
+  }
+
+  /// Creates a name from the provided labels (most specific label first, root label excluded).
+  /// Fails if any label is empty or exceeds 63 octets, or if the whole name exceeds 255 octets.
+  pub fn from_labels<I, L>(labels: I) -> Result<Self, DrasilDNSError>
+  where
+    I: IntoIterator<Item = L>,
+    L: AsRef<[u8]>,
+  {
+    let mut name = Self::root();
+    let mut size = 1;
+
+    for label in labels {
+      let label = label.as_ref();
+      if label.is_empty() {
+        return Err(DrasilDNSError::InvalidData { msg: "domain names can't contain empty labels".to_string() });
+      }
+      if label.len() > MAX_LABEL_LENGTH {
+        return Err(DrasilDNSError::LabelTooLarge { size: label.len() });
+      }
+
+      size += label.len() + 1;
+      name.labels.push(label.to_vec());
+    }
+
+    if size > MAX_NAME_LENGTH {
+      return Err(DrasilDNSError::NameTooLarge { size });
+    }
+
+    Ok(name)
+  }
+
+  /// Iterates over the labels of the name (most specific label first, root label excluded)
+  pub fn labels(&self) -> impl DoubleEndedIterator<Item = &[u8]> + ExactSizeIterator {
+    self.labels.iter().map(|label| label.as_slice())
+  }
+
+  /// Returns the number of labels in the name (root label excluded)
+  pub fn label_count(&self) -> usize {
+    self.labels.len()
+  }
+
+  /// Tells whether this is the root name
+  pub fn is_root(&self) -> bool {
+    self.labels.is_empty()
+  }
+
+  /// Returns size of the name in uncompressed wire format
+  pub fn wire_len(&self) -> usize {
+    self.labels.iter().map(|label| label.len() + 1).sum::<usize>() + 1
+  }
+
+  /// Returns the name with its most specific label removed, `None` for the root name
+  pub fn parent(&self) -> Option<Name> {
+    if self.is_root() {
+      return None;
+    }
+    Some(Self { labels: self.labels[1..].to_vec() })
+  }
+
+  /// Tells whether this name is equal to or below the provided name
+  pub fn is_subdomain_of(&self, other: &Name) -> bool {
+    if other.labels.len() > self.labels.len() {
+      return false;
+    }
+
+    self.labels.iter().rev()
+      .zip(other.labels.iter().rev())
+      .all(|(a, b)| a.eq_ignore_ascii_case(b))
+  }
+
+  /// Returns a copy of the name with all ASCII letters lowercased
+  pub fn to_lowercase(&self) -> Name {
+    Self { labels: self.labels.iter().map(|label| label.to_ascii_lowercase()).collect() }
+  }
+}
+
+impl PartialEq for Name {
+  fn eq(&self, other: &Self) -> bool {
+    self.labels.len() == other.labels.len()
+      && self.labels.iter().zip(&other.labels).all(|(a, b)| a.eq_ignore_ascii_case(b))
+  }
+}
+
+impl Eq for Name {}
+
+impl Hash for Name {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    state.write_usize(self.labels.len());
+    for label in &self.labels {
+      state.write_u8(label.len() as u8);
+      for byte in label {
+        state.write_u8(byte.to_ascii_lowercase());
+      }
+    }
+  }
+}
+
+impl PartialOrd for Name {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Name {
+  /// Canonical DNS name order (RFC4034 section 6.1), names are compared label by label starting from the least specific one.
+  fn cmp(&self, other: &Self) -> Ordering {
+    let lower = |label: &Vec<u8>| label.to_ascii_lowercase();
+    self.labels.iter().rev().map(lower)
+      .cmp(other.labels.iter().rev().map(lower))
+  }
+}
+
+impl fmt::Display for Name {
+  /// Writes the name in presentation format (RFC1035 section 5.1), special characters are escaped using `\X` and `\DDD` forms.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_root() {
+      return f.write_str(".");
+    }
+
+    for label in &self.labels {
+      for &byte in label {
+        match byte {
+          b'.' | b'\\' | b'"' | b'(' | b')' | b';' | b'@' | b'$' => write!(f, "\\{}", byte as char)?,
+          0x21..=0x7E => write!(f, "{}", byte as char)?,
+          _ => write!(f, "\\{:03}", byte)?,
+        }
+      }
+      f.write_str(".")?;
+    }
+
+    Ok(())
+  }
+}
+
+impl fmt::Debug for Name {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Name(\"{}\")", self)
+  }
+}
+
+impl FromStr for Name {
+  type Err = DrasilDNSError;
+
+  /// Parses a name in presentation format, the trailing dot is optional as every name is treated as fully qualified.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s == "." || s.is_empty() {
+      return Ok(Self::root());
+    }
+
+    let invalid = |msg: &str| DrasilDNSError::InvalidData { msg: format!("{} in domain name \"{}\"", msg, s) };
+
+    let mut labels = vec![];
+    let mut label = vec![];
+    let mut bytes = s.bytes().peekable();
+
+    while let Some(byte) = bytes.next() {
+      match byte {
+        b'.' => {
+          if label.is_empty() {
+            return Err(invalid("empty label"));
+          }
+          labels.push(std::mem::take(&mut label));
+        },
+
+        b'\\' => match bytes.next() {
+          Some(d) if d.is_ascii_digit() => {
+            let mut val = (d - b'0') as u16;
+            for _ in 0..2 {
+              match bytes.next() {
+                Some(d) if d.is_ascii_digit() => val = val * 10 + (d - b'0') as u16,
+                _ => return Err(invalid("incomplete \\DDD escape")),
+              }
+            }
+            if val > 255 {
+              return Err(invalid("out of range \\DDD escape"));
+            }
+            label.push(val as u8);
+          },
+          Some(c) => label.push(c),
+          None => return Err(invalid("dangling escape")),
+        },
+
+        b => label.push(b),
+      }
+
+      if bytes.peek().is_none() && !label.is_empty() {
+        labels.push(std::mem::take(&mut label));
+      }
+    }
+
+    Self::from_labels(labels)
+  }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Name {
+  /// Generates a valid name, labels are added as long as the whole name stays within 255 octets
+  fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+    let mut name = Self::root();
+    let mut size = 1;
+
+    while u.arbitrary::<bool>()? {
+      let len = u.int_in_range(1..=MAX_LABEL_LENGTH)?;
+      if size + len + 1 > MAX_NAME_LENGTH {
+        break;
+      }
+
+      size += len + 1;
+      name.labels.push(u.bytes(len)?.to_vec());
+    }
+
+    Ok(name)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Name {
+  /// Serializes the name in presentation format
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Name {
+  /// Deserializes a name in presentation format
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let text = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+    text.parse().map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashSet;
+
+  #[test]
+  fn name_text_round_trip() {
+    let name: Name = "www.Example.com".parse().expect("Failed to parse name");
+    assert_eq!(name.to_string(), "www.Example.com.", "Name not displayed correctly");
+    assert_eq!(name.label_count(), 3, "Incorrect label count");
+    assert_eq!(name.wire_len(), 17, "Incorrect wire length");
+
+    let escaped = Name::from_labels([&b"a.b"[..], &[0, 200, b'\\'][..], &b"com"[..]]).expect("Failed to create name");
+    assert_eq!(escaped.to_string(), "a\\.b.\\000\\200\\\\.com.", "Name not escaped correctly");
+
+    let parsed: Name = escaped.to_string().parse().expect("Failed to parse escaped name");
+    assert_eq!(parsed.labels().collect::<Vec<_>>(), escaped.labels().collect::<Vec<_>>(), "Labels not equal after display+parse");
+
+    assert!("a..com".parse::<Name>().is_err(), "Empty label accepted");
+    assert!("a\\25".parse::<Name>().is_err(), "Incomplete escape accepted");
+    assert!("a".repeat(64).parse::<Name>().is_err(), "Label larger than 63 octets accepted");
+    assert!(vec!["a".repeat(63); 4].join(".").parse::<Name>().is_err(), "Name larger than 255 octets accepted");
+  }
+
+  #[test]
+  fn name_comparison() {
+    let lower: Name = "example.com".parse().unwrap();
+    let upper: Name = "EXAMPLE.COM.".parse().unwrap();
+    assert_eq!(lower, upper, "Names differing in case are not equal");
+    assert_eq!(upper.labels().next(), Some(&b"EXAMPLE"[..]), "Original case not preserved");
+
+    let set: HashSet<Name> = [lower.clone(), upper].into_iter().collect();
+    assert_eq!(set.len(), 1, "Names differing in case hash differently");
+
+    // example from RFC4034 section 6.1
+    let ordered: Vec<Name> = [
+      "example", "a.example", "yljkjljk.a.example", "Z.a.example", "zABC.a.EXAMPLE",
+      "z.example", "\\001.z.example", "*.z.example", "\\200.z.example",
+    ].iter().map(|s| s.parse().unwrap()).collect();
+
+    let mut shuffled = ordered.clone();
+    shuffled.reverse();
+    shuffled.sort();
+    assert_eq!(shuffled, ordered, "Names not in canonical order");
+
+    let www: Name = "www.example.com".parse().unwrap();
+    assert!(www.is_subdomain_of(&lower), "Subdomain not detected");
+    assert!(lower.is_subdomain_of(&lower), "Name is not a subdomain of itself");
+    assert!(!lower.is_subdomain_of(&www), "Parent detected as subdomain");
+    assert!(www.is_subdomain_of(&Name::root()), "Name is not a subdomain of root");
+    assert_eq!(www.parent(), Some(lower), "Incorrect parent");
+    assert_eq!(Name::root().parent(), None, "Root has a parent");
+  }
+}
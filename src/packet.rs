@@ -2,19 +2,26 @@
 /// Provides a builder struct for Packet
 pub mod builder;
 
+/// Provides a zero-copy view over packet data
+pub mod view;
+
+/// Provides the dig-style `Display` implementation of `Packet`
+mod dig;
+
 // ===== Imports =====
 use crate::{
   buffer::Buffer,
   error::DrasilDNSError,
-  header::Header,
+  header::{Header, ResponseCode},
   question::Question,
-  record::Record,
+  record::{rrset::RRset, Record},
 };
 // ===================
 
 /// # Packet
 /// Struct representing a single DNS packet.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Packet {
   pub header: Header,
   pub questions: Vec<Question>,
@@ -25,6 +32,7 @@ pub struct Packet {
 
 impl Packet {
   /// Get a DNS packet from bytes.
+  /// Never panics, whatever the input (enforced by the `packet_parse` fuzz target).
   pub fn parse(data: &[u8]) -> Result<Self, DrasilDNSError> {
     let mut buff: Buffer = data.into();
 
@@ -70,28 +78,175 @@ impl Packet {
     })
   }
 
-  /// Convert a DNS packet into bytes
+  /// Returns the full 12-bit response code, made of the 4 bits stored in the header and the `extended_rcode`
+  /// of the OPT record (RFC6891 section 6.1.3). Without an OPT record only the header value is used.
+  pub fn response_code(&self) -> ResponseCode {
+    let low = u16::from(self.header.response_code) & 0x000F;
+    let high = self.additional.iter()
+      .find_map(|r| match r {
+        Record::OPT { extended_rcode, .. } => Some(*extended_rcode as u16),
+        _ => None,
+      })
+      .unwrap_or(0);
+
+    ResponseCode::from((high << 4) | low)
+  }
+
+  /// Sets the full 12-bit response code, splitting it between the header and the OPT record (if any).
+  /// Fails with `DrasilDNSError::InvalidData` if the code doesn't fit in 12 bits, or needs more than 4 bits
+  /// while the packet has no OPT record. The packet is left unchanged on failure.
+  pub fn set_response_code(&mut self, code: ResponseCode) -> Result<(), DrasilDNSError> {
+    let value = u16::from(code);
+    if value > 0x0FFF {
+      return Err(DrasilDNSError::InvalidData { msg: format!("response code {} doesn't fit in 12 bits", value) });
+    }
+
+    let opt = self.additional.iter_mut().find_map(|r| match r {
+      Record::OPT { extended_rcode, .. } => Some(extended_rcode),
+      _ => None,
+    });
+
+    match opt {
+      Some(extended_rcode) => *extended_rcode = (value >> 4) as u8,
+      None if value > 0x000F => {
+        return Err(DrasilDNSError::InvalidData { msg: format!("response code {} requires an OPT record", value) });
+      },
+      None => {},
+    }
+
+    self.header.response_code = ResponseCode::from(value & 0x000F);
+    Ok(())
+  }
+
+  /// Convert a DNS packet into bytes.
+  /// Domain names are compressed (RFC1035 section 4.1.4) wherever the record format allows it.
+  /// The packet is not size-limited, use `to_bytes_with_limit` for transports which impose a maximum message size.
   pub fn to_bytes(&self) -> Result<Vec<u8>, DrasilDNSError> {
-    let mut buff = Buffer::default();
-    self.header.write_bytes(&mut buff)?;
+    let mut buff = Buffer::with_capacity(0);
+    buff.set_expandable(true);
+    buff.enable_compression();
+
+    self.write_bytes(&mut buff)?;
+    Ok(buff.into())
+  }
+
+  /// Convert a DNS packet into at most `max` bytes.
+  /// In case the packet doesn't fit, whole RRsets are dropped from the additional, then authority and then answer section
+  /// and the truncated message flag is set in the header. The OPT record (if any) is always kept.
+  /// Fails with `DrasilDNSError::EOF` if even the header, questions and OPT record don't fit.
+  pub fn to_bytes_with_limit(&self, max: usize) -> Result<Vec<u8>, DrasilDNSError> {
+    let mut buff = Buffer::with_capacity(max);
+    buff.enable_compression();
+
+    match self.write_bytes(&mut buff) {
+      Ok(()) => return Ok(buff.into()),
+      Err(DrasilDNSError::EOF) => {},
+      Err(e) => return Err(e),
+    }
+
+    let opt = self.additional.iter().find(|r| matches!(r, Record::OPT { .. }));
+    let mut opt_len = 0;
+    if let Some(opt) = opt {
+      let mut b = Buffer::with_capacity(0);
+      b.set_expandable(true);
+      opt.write_bytes(&mut b)?;
+      opt_len = b.pos();
+    }
+
+    if opt_len > max {
+      return Err(DrasilDNSError::EOF);
+    }
+
+    // space for the OPT record is reserved upfront so it can always be appended at the end
+    let mut buff = Buffer::with_capacity(max - opt_len);
+    buff.enable_compression();
+
+    let mut header = self.header;
+    header.is_truncated_message = true;
+    header.write_bytes(&mut buff)?;
 
     for q in &self.questions {
       q.write_bytes(&mut buff)?;
     }
 
+    let mut counts = [0_u16; 3];
+    let sections = [&self.answers, &self.authority, &self.additional];
+
+    'sections: for (count, section) in counts.iter_mut().zip(sections) {
+      for rrset in RRset::group(section.iter().cloned()) {
+        let res = buff.write_transaction(|buff| {
+          for r in rrset.records() {
+            r.write_bytes(buff)?;
+          }
+          Ok(())
+        });
+
+        match res {
+          Ok(()) => *count += rrset.records().len() as u16,
+          Err(DrasilDNSError::EOF) => break 'sections,
+          Err(e) => return Err(e),
+        }
+      }
+    }
+
+    if let Some(opt) = opt {
+      buff.set_expandable(true);
+      opt.write_bytes(&mut buff)?;
+      counts[2] += 1;
+    }
+
+    header.question_count = self.questions.len() as u16;
+    header.answer_count = counts[0];
+    header.authority_count = counts[1];
+    header.additional_count = counts[2];
+
+    let end = buff.pos();
+    buff.seek(0);
+    header.write_bytes(&mut buff)?;
+    buff.seek(end);
+
+    Ok(buff.into())
+  }
+
+  fn write_bytes(&self, buff: &mut Buffer) -> Result<(), DrasilDNSError> {
+    self.header.write_bytes(buff)?;
+
+    for q in &self.questions {
+      q.write_bytes(buff)?;
+    }
+
     for r in &self.answers {
-      r.write_bytes(&mut buff)?;
+      r.write_bytes(buff)?;
     }
 
     for r in &self.authority {
-      r.write_bytes(&mut buff)?;
+      r.write_bytes(buff)?;
     }
 
     for r in &self.additional {
-      r.write_bytes(&mut buff)?;
+      r.write_bytes(buff)?;
     }
 
-    Ok(buff.into())
+    Ok(())
+  }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Packet {
+  /// Generates a packet whose header counts match its sections
+  fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+    let mut header: Header = u.arbitrary()?;
+    let questions: Vec<Question> = u.arbitrary()?;
+    let answers: Vec<Record> = u.arbitrary()?;
+    let authority: Vec<Record> = u.arbitrary()?;
+    let additional: Vec<Record> = u.arbitrary()?;
+
+    header.question_count = questions.len() as u16;
+    header.answer_count = answers.len() as u16;
+    header.authority_count = authority.len() as u16;
+    header.additional_count = additional.len() as u16;
+
+    Ok(Self { header, questions, answers, authority, additional })
   }
 }
 
@@ -100,7 +255,9 @@ mod tests {
   use super::*;
   use std::net::Ipv4Addr;
   use crate::{
-    header::{RequestKind, ResponseCode},
+    name::Name,
+    packet::builder::PacketBuilder,
+    header::{Opcode, RequestKind, ResponseCode},
     types::{RecordClass, RecordType},
     record::edns::EDNSOption,
   };
@@ -111,11 +268,14 @@ mod tests {
       header: Header {
         id: 100,
         request_kind: RequestKind::Query,
-        opcode: 10,
+        opcode: Opcode::Unknown(10),
         is_authoritative_answer: false,
         is_truncated_message: false,
         is_recursion_desired: true,
         is_recursion_available: false,
+        z: false,
+        authentic_data: false,
+        checking_disabled: false,
         response_code: ResponseCode::NOERROR,
         question_count: 1,
         answer_count: 1,
@@ -124,14 +284,14 @@ mod tests {
       },
       questions: vec![
         Question {
-          name: vec!["google".to_string(), "com".to_string()],
+          name: "google.com".parse().unwrap(),
           record_type: RecordType::A,
           record_class: RecordClass::IN,
         }
       ],
       answers: vec![
         Record::A {
-          domain: vec!["google".to_string(), "com".to_string()],
+          domain: "google.com".parse().unwrap(),
           addr: Ipv4Addr::from_bits(0x10101010),
           ttl: 60,
           class: RecordClass::IN,
@@ -153,16 +313,164 @@ mod tests {
       ],
     };
 
-    let mut b = Buffer::with_capacity(0);
-    b.set_expandable(true);
-
     let data= packet.to_bytes()
       .expect("Failed to write packet");
-    assert!(data.len() >= 512, "DNS packet is less than minimum size of 512 bytes");
+
+    let limited = packet.to_bytes_with_limit(512)
+      .expect("Failed to write packet with limit");
+    assert_eq!(data, limited, "Packet which fits the limit was written differently");
 
     let packet_after_read = Packet::parse(&data)
       .expect("Failed to read packet");
 
     assert_eq!(packet, packet_after_read, "Packet not equal after write+read");
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn packet_name_compression() {
+    let zone: Name = "example.com".parse().unwrap();
+    let name = |label: &str| format!("{}.example.com", label).parse::<Name>().unwrap();
+
+    let mut builder = PacketBuilder::new(7)
+      .with_request_kind(RequestKind::Response)
+      .add_question(Question {
+        name: zone.clone(),
+        record_type: RecordType::MX,
+        record_class: RecordClass::IN,
+      });
+
+    for label in ["mx1", "mx2", "mx3", "mx4"] {
+      builder = builder
+        .add_answer(Record::MX { domain: zone.clone(), priority: 10, host: name(label), ttl: 60, class: RecordClass::IN })
+        .add_additional(Record::A { domain: name(label), addr: Ipv4Addr::from_bits(0x7F000001), ttl: 60, class: RecordClass::IN });
+    }
+
+    builder = builder
+      .add_authority(Record::NS { domain: zone.clone(), host: name("ns1"), ttl: 60, class: RecordClass::IN })
+      .add_authority(Record::NS { domain: zone.clone(), host: name("ns2"), ttl: 60, class: RecordClass::IN });
+
+    let packet = builder.build().expect("Failed to build packet");
+    let data = packet.to_bytes().expect("Failed to write packet");
+
+    // "example.com" is only written once, every other occurrence is a pointer to it
+    let occurrences = data.windows(8).filter(|w| w == b"\x07example").count();
+    assert_eq!(occurrences, 1, "Names were not compressed");
+
+    let packet_after_read = Packet::parse(&data).expect("Failed to read packet");
+    assert_eq!(packet, packet_after_read, "Packet not equal after write+read");
+  }
+
+  #[test]
+  fn packet_truncation() {
+    let name = |label: &str| format!("{}.example.com", label).parse::<Name>().unwrap();
+    let a = |label: &str, addr: u32| Record::A { domain: name(label), addr: Ipv4Addr::from_bits(addr), ttl: 60, class: RecordClass::IN };
+
+    let mut builder = PacketBuilder::new(9)
+      .with_request_kind(RequestKind::Response)
+      .add_question(Question {
+        name: name("www"),
+        record_type: RecordType::A,
+        record_class: RecordClass::IN,
+      });
+
+    for i in 0..4 {
+      builder = builder.add_answer(a("www", i));
+    }
+    for i in 0..20 {
+      builder = builder.add_additional(a("extra", i));
+    }
+
+    let packet = builder
+      .add_additional(a("other", 1))
+      .add_additional(Record::OPT {
+        udp_payload_size: 1232,
+        extended_rcode: 0,
+        version: 0,
+        dnssec_ok: false,
+        options: vec![EDNSOption::Cookie { client: 100, server: None }],
+      })
+      .build().expect("Failed to build packet");
+
+    let full = packet.to_bytes().expect("Failed to write packet");
+    assert!(full.len() > 200, "Test packet is too small");
+
+    let data = packet.to_bytes_with_limit(200).expect("Failed to write packet with limit");
+    assert!(data.len() <= 200, "Packet exceeds the limit");
+
+    let truncated = Packet::parse(&data).expect("Failed to read truncated packet");
+    assert!(truncated.header.is_truncated_message, "Truncated message flag not set");
+    assert_eq!(truncated.answers, packet.answers, "Answer section should have been kept");
+    assert_eq!(truncated.additional, packet.additional[21..].to_vec(), "Only the OPT record should remain in additional section");
+
+    packet.to_bytes_with_limit(20).expect_err("Packet written even though question doesn't fit");
+  }
+
+  #[test]
+  fn packet_extended_rcode() {
+    let opt = Record::OPT { udp_payload_size: 1232, extended_rcode: 0, version: 0, dnssec_ok: false, options: vec![] };
+    let mut packet = PacketBuilder::new(7)
+      .with_request_kind(RequestKind::Response)
+      .with_opcode(Opcode::NOTIFY)
+      .authentic_data()
+      .checking_disabled()
+      .with_response_code(ResponseCode::BADCOOKIE)
+      .add_additional(opt)
+      .build().expect("Failed to build packet");
+
+    assert_eq!(u16::from(packet.header.response_code), 7, "Lower bits of response code not stored in header");
+    assert_eq!(packet.response_code(), ResponseCode::BADCOOKIE, "Effective response code incorrect");
+    assert!(matches!(packet.additional[0], Record::OPT { extended_rcode: 1, .. }), "Upper bits of response code not stored in OPT");
+
+    packet.set_response_code(ResponseCode::NXDOMAIN).expect("Failed to set response code");
+    assert_eq!(packet.response_code(), ResponseCode::NXDOMAIN, "Effective response code not updated");
+
+    packet.additional.clear();
+    packet.set_response_code(ResponseCode::BADVERS).expect_err("Extended response code set without OPT record");
+    packet.set_response_code(ResponseCode::Unknown(20)).expect_err("Extended response code set without OPT record");
+    packet.set_response_code(ResponseCode::Unknown(0x1000)).expect_err("Response code larger than 12 bits accepted");
+    assert_eq!(packet.response_code(), ResponseCode::NXDOMAIN, "Response code changed by a failed update");
+
+    PacketBuilder::new(8)
+      .with_response_code(ResponseCode::BADVERS)
+      .build()
+      .expect_err("Packet with extended response code built without OPT record");
+  }
+
+  #[test]
+  fn packet_parse_untrusted() {
+    // records whose RDLENGTH is too small for their fixed fields
+    for record_type in [RecordType::DS, RecordType::RRSIG, RecordType::NSEC, RecordType::DNSKEY, RecordType::NSEC3] {
+      let mut data = vec![0, 1, 0x81, 0x80, 0, 0, 0, 1, 0, 0, 0, 0, 0];
+      data.extend_from_slice(&u16::from(record_type).to_be_bytes());
+      data.extend_from_slice(&[0, 1, 0, 0, 0, 60, 0, 0, 0, 1]);
+      data.extend_from_slice(&[0xFF; 32]);
+
+      assert!(Packet::parse(&data).is_err(), "{:?} with too small RDLENGTH accepted", record_type);
+    }
+
+    let packet = PacketBuilder::new(1)
+      .add_question(Question { name: "example.com".parse().unwrap(), record_type: RecordType::A, record_class: RecordClass::IN })
+      .add_answer(Record::MX { domain: "example.com".parse().unwrap(), priority: 10, host: "mx.example.com".parse().unwrap(), ttl: 60, class: RecordClass::IN })
+      .add_additional(Record::OPT {
+        udp_payload_size: 1232,
+        extended_rcode: 0,
+        version: 0,
+        dnssec_ok: true,
+        options: vec![EDNSOption::Cookie { client: 100, server: None }],
+      })
+      .build().expect("Failed to build packet");
+    let data = packet.to_bytes().expect("Failed to write packet");
+
+    // truncated and corrupted packets must be rejected or parsed, never panic
+    for len in 0..data.len() {
+      let _ = Packet::parse(&data[..len]);
+    }
+    for i in 0..data.len() {
+      for byte in [0x00, 0x01, 0x3F, 0x7F, 0xC0, 0xFF] {
+        let mut corrupted = data.clone();
+        corrupted[i] = byte;
+        let _ = Packet::parse(&corrupted);
+      }
+    }
+  }
+}
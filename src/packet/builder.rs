@@ -1,7 +1,7 @@
 
 // ===== Imports =====
 use crate::{
-  header::{Header, RequestKind, ResponseCode}, packet::Packet, question::Question, record::Record
+  error::DrasilDNSError, header::{Header, Opcode, RequestKind, ResponseCode}, packet::Packet, question::Question, record::Record
 };
 // ===================
 
@@ -10,11 +10,13 @@ use crate::{
 pub struct PacketBuilder {
   id: u16,
   request_kind: RequestKind,
-  opcode: u8,
+  opcode: Opcode,
   is_authoritative_answer: bool,
   is_truncated_message: bool,
   is_recursion_desired: bool,
   is_recursion_available: bool,
+  authentic_data: bool,
+  checking_disabled: bool,
   response_code: ResponseCode,
 
   questions: Vec<Question>,
@@ -29,11 +31,13 @@ impl PacketBuilder {
     Self {
       id,
       request_kind: RequestKind::Query,
-      opcode: 0,
+      opcode: Opcode::QUERY,
       is_authoritative_answer: false,
       is_truncated_message: false,
       is_recursion_desired: false,
       is_recursion_available: false,
+      authentic_data: false,
+      checking_disabled: false,
       response_code: ResponseCode::NOERROR,
       questions: vec![],
       answers: vec![],
@@ -43,8 +47,10 @@ impl PacketBuilder {
   }
 
   /// Build a new packet from the specified options.
-  pub fn build(self) -> Packet {
-    Packet {
+  /// Fails with `DrasilDNSError::InvalidData` if the response code doesn't fit in 12 bits,
+  /// or needs more than 4 bits while no OPT record was added to carry the upper bits.
+  pub fn build(self) -> Result<Packet, DrasilDNSError> {
+    let mut packet = Packet {
       header: Header {
         id: self.id,
         request_kind: self.request_kind,
@@ -53,6 +59,9 @@ impl PacketBuilder {
         is_truncated_message: self.is_truncated_message,
         is_recursion_desired: self.is_recursion_desired,
         is_recursion_available: self.is_recursion_available,
+        z: false,
+        authentic_data: self.authentic_data,
+        checking_disabled: self.checking_disabled,
         response_code: self.response_code,
         question_count: self.questions.len() as u16,
         answer_count: self.answers.len() as u16,
@@ -63,7 +72,10 @@ impl PacketBuilder {
       answers: self.answers,
       authority: self.authority,
       additional: self.additional,
-    }
+    };
+
+    packet.set_response_code(self.response_code)?;
+    Ok(packet)
   }
 
   /// Specify the request kind for the packet
@@ -73,8 +85,15 @@ impl PacketBuilder {
   }
 
   /// Set opcode for the packet
-  pub fn with_opcode(mut self, opcode: u8) -> Self {
-    self.opcode = opcode & 0b00001111;
+  pub fn with_opcode(mut self, opcode: Opcode) -> Self {
+    self.opcode = opcode;
+    self
+  }
+
+  /// Set response code for the packet, only its lower 4 bits fit in the header
+  /// and the upper bits are stored in the OPT record (which must be added for extended codes)
+  pub fn with_response_code(mut self, code: ResponseCode) -> Self {
+    self.response_code = code;
     self
   }
 
@@ -102,6 +121,18 @@ impl PacketBuilder {
     self
   }
 
+  /// Sets authentic data flag to true
+  pub fn authentic_data(mut self) -> Self {
+    self.authentic_data = true;
+    self
+  }
+
+  /// Sets checking disabled flag to true
+  pub fn checking_disabled(mut self) -> Self {
+    self.checking_disabled = true;
+    self
+  }
+
   /// Add a new question to the packet
   pub fn add_question(mut self, question: Question) -> Self {
     self.questions.push(question);
@@ -0,0 +1,252 @@
+
+// ===== Imports =====
+use std::{fmt, net::{Ipv4Addr, Ipv6Addr}};
+use crate::{
+  header::RequestKind,
+  packet::Packet,
+  presentation,
+  record::{edns::EDNSOption, Record},
+};
+// ===================
+
+/// Returns the name of an Extended DNS Error info-code (RFC8914 section 5.2, IANA registry)
+fn ede_name(info_code: u16) -> Option<&'static str> {
+  Some(match info_code {
+    0 => "Other Error",
+    1 => "Unsupported DNSKEY Algorithm",
+    2 => "Unsupported DS Digest Type",
+    3 => "Stale Answer",
+    4 => "Forged Answer",
+    5 => "DNSSEC Indeterminate",
+    6 => "DNSSEC Bogus",
+    7 => "Signature Expired",
+    8 => "Signature Not Yet Valid",
+    9 => "DNSKEY Missing",
+    10 => "RRSIGs Missing",
+    11 => "No Zone Key Bit Set",
+    12 => "NSEC Missing",
+    13 => "Cached Error",
+    14 => "Not Ready",
+    15 => "Blocked",
+    16 => "Censored",
+    17 => "Filtered",
+    18 => "Prohibited",
+    19 => "Stale NXDOMAIN Answer",
+    20 => "Not Authoritative",
+    21 => "Not Supported",
+    22 => "No Reachable Authority",
+    23 => "Network Error",
+    24 => "Invalid Data",
+    25 => "Signature Expired before Valid",
+    26 => "Too Early",
+    27 => "Unsupported NSEC3 Iterations Value",
+    28 => "Unable to conform to policy",
+    29 => "Synthesized",
+    30 => "Invalid Query Type",
+    _ => return None,
+  })
+}
+
+/// Writes data as space separated hex octets followed by its printable form, like dig does for NSID
+fn fmt_hex_and_text(data: &[u8]) -> String {
+  let hex: Vec<String> = data.iter().map(|b| format!("{:02x}", b)).collect();
+  let text: String = data.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
+  format!("{} (\"{}\")", hex.join(" "), text)
+}
+
+/// The address of a client subnet option is stored in as many octets as the source netmask needs,
+/// this places them at the start of an address of the family's length
+fn subnet_addr(addr: u128, source_netmask: u8, family_len: usize) -> Vec<u8> {
+  let len = match source_netmask {
+    0..=8 => 1,
+    9..=16 => 2,
+    17..=32 => 4,
+    33..=64 => 8,
+    _ => 16,
+  };
+
+  let mut octets = addr.to_be_bytes()[16 - len..].to_vec();
+  octets.resize(family_len, 0);
+  octets
+}
+
+/// Writes an EDNS(0) option as a line of the OPT pseudosection
+fn fmt_option(f: &mut fmt::Formatter<'_>, option: &EDNSOption) -> fmt::Result {
+  match option {
+    EDNSOption::NSID { data } => writeln!(f, "; NSID: {}", fmt_hex_and_text(data.as_bytes())),
+
+    EDNSOption::Cookie { client, server } => {
+      write!(f, "; COOKIE: {:016x}", client)?;
+      if let Some(server) = server {
+        write!(f, "{:016x}", server)?;
+      }
+      writeln!(f)
+    },
+
+    EDNSOption::EDE { info_code, extra_text } => {
+      write!(f, "; EDE: {}", info_code)?;
+      if let Some(name) = ede_name(*info_code) {
+        write!(f, " ({})", name)?;
+      }
+      if !extra_text.is_empty() {
+        write!(f, ": ({})", extra_text)?;
+      }
+      writeln!(f)
+    },
+
+    EDNSOption::ClientSubnet { family, source_netmask, scope_netmask, addr } => {
+      let addr = match family {
+        1 => <[u8; 4]>::try_from(subnet_addr(*addr, *source_netmask, 4)).map(|o| Ipv4Addr::from(o).to_string()),
+        _ => <[u8; 16]>::try_from(subnet_addr(*addr, *source_netmask, 16)).map(|o| Ipv6Addr::from(o).to_string()),
+      }.map_err(|_| fmt::Error)?;
+      writeln!(f, "; CLIENT-SUBNET: {}/{}/{}", addr, source_netmask, scope_netmask)
+    },
+    EDNSOption::EcsIPv4 { source_netmask, scope_netmask, addr } => {
+      writeln!(f, "; CLIENT-SUBNET: {}/{}/{}", Ipv4Addr::from(*addr), source_netmask, scope_netmask)
+    },
+    EDNSOption::EcsIPv6 { source_netmask, scope_netmask, addr } => {
+      writeln!(f, "; CLIENT-SUBNET: {}/{}/{}", Ipv6Addr::from(*addr), source_netmask, scope_netmask)
+    },
+
+    // the timeout is given in units of 100 milliseconds (RFC7828 section 3.1)
+    EDNSOption::KeepAlive { timeout } => writeln!(f, "; TCP-KEEPALIVE: {}.{} secs", timeout / 10, timeout % 10),
+    EDNSOption::Padding { len } => writeln!(f, "; PADDING: ({} bytes)", len),
+    EDNSOption::KeyTag { tags } => {
+      let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+      writeln!(f, "; KEY-TAG: {}", tags.join(", "))
+    },
+    EDNSOption::ChainQuery { flags, qname_min_length } => writeln!(f, "; CHAIN: flags {}, qname min length {}", flags, qname_min_length),
+    EDNSOption::Unknown { code, data, .. } => writeln!(f, "; OPT={}: {}", code, presentation::fmt_hex(data)),
+  }
+}
+
+/// Writes a section header followed by its records, OPT records are shown in the OPT pseudosection instead
+fn fmt_section(f: &mut fmt::Formatter<'_>, name: &str, records: &[Record]) -> fmt::Result {
+  let mut records = records.iter().filter(|r| !matches!(r, Record::OPT { .. })).peekable();
+  if records.peek().is_none() {
+    return Ok(());
+  }
+
+  writeln!(f, "\n;; {} SECTION:", name)?;
+  for record in records {
+    write!(f, "{}\t{}\t{}\t{}", record.domain(), record.ttl(), record.class(), record.record_type())?;
+
+    let rdata = record.rdata_to_string().map_err(|_| fmt::Error)?;
+    if !rdata.is_empty() {
+      write!(f, "\t{}", rdata)?;
+    }
+    writeln!(f)?;
+  }
+
+  Ok(())
+}
+
+impl fmt::Display for Packet {
+  /// Writes the packet the way dig does: the header and flags lines, the OPT pseudosection (if the packet
+  /// has an OPT record) and the question, answer, authority and additional sections in presentation format.
+  /// The status is the full response code, including the extended bits of the OPT record.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let h = &self.header;
+    writeln!(f, ";; ->>HEADER<<- opcode: {}, status: {}, id: {}", h.opcode, self.response_code(), h.id)?;
+
+    let flags = [
+      (h.request_kind == RequestKind::Response, "qr"),
+      (h.is_authoritative_answer, "aa"),
+      (h.is_truncated_message, "tc"),
+      (h.is_recursion_desired, "rd"),
+      (h.is_recursion_available, "ra"),
+      (h.z, "z"),
+      (h.authentic_data, "ad"),
+      (h.checking_disabled, "cd"),
+    ];
+    let flags: Vec<&str> = flags.iter().filter(|(set, _)| *set).map(|(_, name)| *name).collect();
+    writeln!(
+      f,
+      ";; flags: {}; QUERY: {}, ANSWER: {}, AUTHORITY: {}, ADDITIONAL: {}",
+      flags.join(" "), h.question_count, h.answer_count, h.authority_count, h.additional_count,
+    )?;
+
+    for record in &self.additional {
+      if let Record::OPT { udp_payload_size, version, dnssec_ok, options, .. } = record {
+        writeln!(f, "\n;; OPT PSEUDOSECTION:")?;
+        writeln!(f, "; EDNS: version: {}, flags:{}; udp: {}", version, if *dnssec_ok { " do" } else { "" }, udp_payload_size)?;
+        for option in options {
+          fmt_option(f, option)?;
+        }
+      }
+    }
+
+    if !self.questions.is_empty() {
+      writeln!(f, "\n;; QUESTION SECTION:")?;
+      for q in &self.questions {
+        writeln!(f, ";{}\t{}\t{}", q.name, q.record_class, q.record_type)?;
+      }
+    }
+
+    fmt_section(f, "ANSWER", &self.answers)?;
+    fmt_section(f, "AUTHORITY", &self.authority)?;
+    fmt_section(f, "ADDITIONAL", &self.additional)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{header::ResponseCode, packet::builder::PacketBuilder};
+  use super::*;
+
+  #[test]
+  fn packet_dig_display() {
+    let packet = PacketBuilder::new(4242)
+      .with_request_kind(RequestKind::Response)
+      .recursion_desired()
+      .recursion_available()
+      .authentic_data()
+      .add_question("example.com. A".parse().unwrap())
+      .add_answer("example.com. 300 IN A 192.0.2.1".parse().unwrap())
+      .add_authority("example.com. 300 IN NS ns1.example.com.".parse().unwrap())
+      .add_additional(Record::OPT {
+        udp_payload_size: 1232,
+        extended_rcode: 0,
+        version: 0,
+        dnssec_ok: true,
+        options: vec![
+          EDNSOption::Cookie { client: 0x0102030405060708, server: Some(0x1112131415161718) },
+          EDNSOption::NSID { data: "ns-1".to_string() },
+          EDNSOption::EDE { info_code: 18, extra_text: "blocked by policy".to_string() },
+          EDNSOption::ClientSubnet { family: 1, source_netmask: 24, scope_netmask: 0, addr: 0xC0000200 },
+        ],
+      })
+      .with_response_code(ResponseCode::BADVERS)
+      .build().expect("Failed to build packet");
+
+    assert_eq!(packet.to_string(), "\
+;; ->>HEADER<<- opcode: QUERY, status: BADVERS, id: 4242
+;; flags: qr rd ra ad; QUERY: 1, ANSWER: 1, AUTHORITY: 1, ADDITIONAL: 1
+
+;; OPT PSEUDOSECTION:
+; EDNS: version: 0, flags: do; udp: 1232
+; COOKIE: 01020304050607081112131415161718
+; NSID: 6e 73 2d 31 (\"ns-1\")
+; EDE: 18 (Prohibited): (blocked by policy)
+; CLIENT-SUBNET: 192.0.2.0/24/0
+
+;; QUESTION SECTION:
+;example.com.\tIN\tA
+
+;; ANSWER SECTION:
+example.com.\t300\tIN\tA\t192.0.2.1
+
+;; AUTHORITY SECTION:
+example.com.\t300\tIN\tNS\tns1.example.com.
+", "Packet not displayed like dig");
+
+    let query = PacketBuilder::new(1).add_question("example.com. AAAA".parse().unwrap()).build().expect("Failed to build packet");
+    assert_eq!(query.to_string(), "\
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 1
+;; flags: ; QUERY: 1, ANSWER: 0, AUTHORITY: 0, ADDITIONAL: 0
+
+;; QUESTION SECTION:
+;example.com.\tIN\tAAAA
+", "Query not displayed like dig");
+  }
+}
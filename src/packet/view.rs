@@ -0,0 +1,464 @@
+// ===== Imports =====
+use std::fmt;
+use crate::{
+  buffer::Buffer,
+  error::DrasilDNSError,
+  header::Header,
+  name::{Name, MAX_LABEL_LENGTH, MAX_NAME_LENGTH},
+  packet::Packet,
+  question::Question,
+  record::{edns::{EDNSOption, EDNSOptionType}, Record},
+  types::{RecordClass, RecordType},
+};
+// ===================
+
+const HEADER_SIZE: usize = 12;
+const MAX_JUMPS: usize = 5;
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16, DrasilDNSError> {
+  match data.get(at..(at + 2)) {
+    Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+    None => Err(DrasilDNSError::EOF),
+  }
+}
+
+fn read_u32(data: &[u8], at: usize) -> Result<u32, DrasilDNSError> {
+  match data.get(at..(at + 4)) {
+    Some(bytes) => Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+    None => Err(DrasilDNSError::EOF),
+  }
+}
+
+/// Validates the name starting at `offset` (following compression pointers) and returns the offset right after it.
+fn skip_name(data: &[u8], offset: usize) -> Result<usize, DrasilDNSError> {
+  let mut i = offset;
+  let mut end = None;
+  let mut jumps = 0;
+  let mut size = 1;
+
+  loop {
+    let len = *data.get(i).ok_or(DrasilDNSError::EOF)?;
+
+    if (len & 0b11000000) == 0b11000000 { // jump
+      if jumps >= MAX_JUMPS {
+        return Err(DrasilDNSError::TooManyJumpsInLabelSequence);
+      }
+
+      let low = *data.get(i + 1).ok_or(DrasilDNSError::EOF)?;
+      if end.is_none() {
+        end = Some(i + 2);
+      }
+
+      i = (((len & 0b00111111) as usize) << 8) | low as usize;
+      jumps += 1;
+    } else if len == 0 {
+      return Ok(end.unwrap_or(i + 1));
+    } else if len as usize > MAX_LABEL_LENGTH {
+      return Err(DrasilDNSError::LabelTooLarge { size: len as usize });
+    } else {
+      size += len as usize + 1;
+      if size > MAX_NAME_LENGTH {
+        return Err(DrasilDNSError::NameTooLarge { size });
+      }
+      if i + 1 + len as usize > data.len() {
+        return Err(DrasilDNSError::EOF);
+      }
+      i += 1 + len as usize;
+    }
+  }
+}
+
+/// # Name Reference
+/// Domain name borrowed from packet data, compression pointers are followed lazily while iterating over its labels.
+#[derive(Clone, Copy)]
+pub struct NameRef<'a> {
+  data: &'a [u8],
+  offset: usize,
+}
+
+impl<'a> NameRef<'a> {
+  fn parse(data: &'a [u8], offset: usize) -> Result<(Self, usize), DrasilDNSError> {
+    let end = skip_name(data, offset)?;
+    Ok((Self { data, offset }, end))
+  }
+
+  /// Iterates over the labels of the name (most specific label first, root label excluded)
+  pub fn labels(&self) -> NameLabels<'a> {
+    NameLabels { data: self.data, pos: self.offset }
+  }
+
+  /// Copies the name into an owned `Name`
+  pub fn to_owned(&self) -> Result<Name, DrasilDNSError> {
+    Name::from_labels(self.labels())
+  }
+}
+
+impl PartialEq<Name> for NameRef<'_> {
+  fn eq(&self, other: &Name) -> bool {
+    self.labels().count() == other.label_count()
+      && self.labels().zip(other.labels()).all(|(a, b)| a.eq_ignore_ascii_case(b))
+  }
+}
+
+impl fmt::Display for NameRef<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(&self.to_owned().map_err(|_| fmt::Error)?, f)
+  }
+}
+
+impl fmt::Debug for NameRef<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "NameRef(\"{}\")", self)
+  }
+}
+
+/// Iterator over labels of a `NameRef`
+#[derive(Clone)]
+pub struct NameLabels<'a> {
+  data: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Iterator for NameLabels<'a> {
+  type Item = &'a [u8];
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let len = *self.data.get(self.pos)? as usize;
+
+      if (len & 0b11000000) == 0b11000000 { // jump
+        let low = *self.data.get(self.pos + 1)? as usize;
+        self.pos = ((len & 0b00111111) << 8) | low;
+        continue;
+      }
+
+      if len == 0 {
+        return None;
+      }
+
+      let label = self.data.get((self.pos + 1)..(self.pos + 1 + len))?;
+      self.pos += 1 + len;
+      return Some(label);
+    }
+  }
+}
+
+/// # Question Reference
+/// Question borrowed from packet data.
+#[derive(Debug, Clone, Copy)]
+pub struct QuestionRef<'a> {
+  pub name: NameRef<'a>,
+  pub record_type: RecordType,
+  pub record_class: RecordClass,
+}
+
+impl<'a> QuestionRef<'a> {
+  fn parse(data: &'a [u8], offset: usize) -> Result<(Self, usize), DrasilDNSError> {
+    let (name, pos) = NameRef::parse(data, offset)?;
+    let record_type = read_u16(data, pos)?.into();
+    let record_class = read_u16(data, pos + 2)?.into();
+
+    Ok((Self { name, record_type, record_class }, pos + 4))
+  }
+
+  /// Copies the question into an owned `Question`
+  pub fn to_owned(&self) -> Result<Question, DrasilDNSError> {
+    Ok(Question {
+      name: self.name.to_owned()?,
+      record_type: self.record_type,
+      record_class: self.record_class,
+    })
+  }
+}
+
+/// # Record Reference
+/// Resource record borrowed from packet data, its RDATA is left undecoded until asked for.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordRef<'a> {
+  pub name: NameRef<'a>,
+  pub record_type: RecordType,
+  pub class: RecordClass,
+  pub ttl: u32,
+  data: &'a [u8],
+  offset: usize,
+  rdata_offset: usize,
+  rdata_len: usize,
+}
+
+impl<'a> RecordRef<'a> {
+  fn parse(data: &'a [u8], offset: usize) -> Result<(Self, usize), DrasilDNSError> {
+    let (name, pos) = NameRef::parse(data, offset)?;
+    let record_type = RecordType::from(read_u16(data, pos)?);
+    let class = RecordClass::from(read_u16(data, pos + 2)?);
+    let ttl = read_u32(data, pos + 4)?;
+
+    let rdata_len = read_u16(data, pos + 8)? as usize;
+    let rdata_offset = pos + 10;
+
+    if rdata_offset + rdata_len > data.len() {
+      return Err(DrasilDNSError::EOF);
+    }
+
+    let record = Self { name, record_type, class, ttl, data, offset, rdata_offset, rdata_len };
+    Ok((record, rdata_offset + rdata_len))
+  }
+
+  /// Returns the raw RDATA of the record
+  pub fn rdata(&self) -> &'a [u8] {
+    &self.data[self.rdata_offset..(self.rdata_offset + self.rdata_len)]
+  }
+
+  /// Reads a domain name starting at the provided offset in RDATA (eg. the host of NS, CNAME or MX records).
+  /// Compression pointers are followed into the rest of the packet.
+  pub fn name_at(&self, offset: usize) -> Result<NameRef<'a>, DrasilDNSError> {
+    if offset >= self.rdata_len {
+      return Err(DrasilDNSError::EOF);
+    }
+
+    let (name, _) = NameRef::parse(self.data, self.rdata_offset + offset)?;
+    Ok(name)
+  }
+
+  /// Iterates over EDNS(0) options in case this is an `OPT` record
+  pub fn options(&self) -> Option<EDNSOptions<'a>> {
+    if self.record_type != RecordType::OPT {
+      return None;
+    }
+    Some(EDNSOptions { data: self.rdata(), pos: 0 })
+  }
+
+  /// Decodes the record into an owned `Record`, reading the packet data in place without copying it.
+  /// Returns `None` for records which would be ignored while parsing a whole packet.
+  pub fn to_owned(&self) -> Result<Option<Record>, DrasilDNSError> {
+    let mut buff: Buffer = self.data.into();
+    buff.seek(self.offset);
+    Record::parse(&mut buff)
+  }
+}
+
+/// # EDNS(0) Option Reference
+/// EDNS(0) option borrowed from the RDATA of an OPT record.
+#[derive(Debug, Clone, Copy)]
+pub struct EDNSOptionRef<'a> {
+  pub code: EDNSOptionType,
+  pub data: &'a [u8],
+}
+
+impl EDNSOptionRef<'_> {
+  /// Decodes the option into an owned `EDNSOption`
+  pub fn to_owned(&self) -> Result<EDNSOption, DrasilDNSError> {
+    let mut buff = Buffer::with_capacity(4 + self.data.len());
+    buff.write_u16(self.code.into())?;
+    buff.write_u16(self.data.len() as u16)?;
+    buff.write_bytes(self.data)?;
+
+    buff.seek(0);
+    EDNSOption::parse(&mut buff)
+  }
+}
+
+/// Iterator over options of an `OPT` record, yields an error (and stops) in case an option overruns the RDATA
+#[derive(Debug, Clone)]
+pub struct EDNSOptions<'a> {
+  data: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Iterator for EDNSOptions<'a> {
+  type Item = Result<EDNSOptionRef<'a>, DrasilDNSError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.pos >= self.data.len() {
+      return None;
+    }
+
+    let res = read_u16(self.data, self.pos).and_then(|code| {
+      let len = read_u16(self.data, self.pos + 2)? as usize;
+      let start = self.pos + 4;
+      let data = self.data.get(start..(start + len)).ok_or(DrasilDNSError::EOF)?;
+      self.pos = start + len;
+      Ok(EDNSOptionRef { code: code.into(), data })
+    });
+
+    if res.is_err() {
+      self.pos = self.data.len();
+    }
+    Some(res)
+  }
+}
+
+/// Iterator over questions of a `PacketRef`
+#[derive(Debug, Clone)]
+pub struct Questions<'a> {
+  data: &'a [u8],
+  pos: usize,
+  remaining: u16,
+}
+
+impl<'a> Iterator for Questions<'a> {
+  type Item = QuestionRef<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+
+    // framing was already validated by `PacketRef::parse`
+    let (question, pos) = QuestionRef::parse(self.data, self.pos).ok()?;
+    self.pos = pos;
+    self.remaining -= 1;
+    Some(question)
+  }
+}
+
+/// Iterator over records of a section of a `PacketRef`
+#[derive(Debug, Clone)]
+pub struct Records<'a> {
+  data: &'a [u8],
+  pos: usize,
+  remaining: u16,
+}
+
+impl<'a> Iterator for Records<'a> {
+  type Item = RecordRef<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+
+    // framing was already validated by `PacketRef::parse`
+    let (record, pos) = RecordRef::parse(self.data, self.pos).ok()?;
+    self.pos = pos;
+    self.remaining -= 1;
+    Some(record)
+  }
+}
+
+/// # Packet Reference
+/// Zero-copy view over the bytes of a DNS packet.
+/// Only the header and the boundaries of each section are decoded upfront, questions and records are decoded lazily while iterating
+/// and borrow their names, RDATA and EDNS(0) options from the underlying data.
+#[derive(Debug, Clone, Copy)]
+pub struct PacketRef<'a> {
+  data: &'a [u8],
+  header: Header,
+  sections: [usize; 4], // offsets of question, answer, authority and additional sections
+}
+
+impl<'a> PacketRef<'a> {
+  /// Get a view over the DNS packet stored in given bytes.
+  /// Framing of every question and record is validated, but their contents are not decoded.
+  pub fn parse(data: &'a [u8]) -> Result<Self, DrasilDNSError> {
+    let mut buff: Buffer = data.get(..HEADER_SIZE).ok_or(DrasilDNSError::EOF)?.into();
+    let header = Header::parse(&mut buff)?;
+
+    let mut sections = [HEADER_SIZE; 4];
+    let mut pos = HEADER_SIZE;
+
+    for _ in 0..header.question_count {
+      (_, pos) = QuestionRef::parse(data, pos)?;
+    }
+    sections[1] = pos;
+
+    let counts = [header.answer_count, header.authority_count, header.additional_count];
+    for (i, count) in counts.into_iter().enumerate() {
+      for _ in 0..count {
+        (_, pos) = RecordRef::parse(data, pos)?;
+      }
+
+      if let Some(offset) = sections.get_mut(i + 2) {
+        *offset = pos;
+      }
+    }
+
+    Ok(Self { data, header, sections })
+  }
+
+  /// Returns the header of the packet
+  pub fn header(&self) -> &Header {
+    &self.header
+  }
+
+  /// Iterates over the question section
+  pub fn questions(&self) -> Questions<'a> {
+    Questions { data: self.data, pos: self.sections[0], remaining: self.header.question_count }
+  }
+
+  /// Iterates over the answer section
+  pub fn answers(&self) -> Records<'a> {
+    Records { data: self.data, pos: self.sections[1], remaining: self.header.answer_count }
+  }
+
+  /// Iterates over the authority section
+  pub fn authority(&self) -> Records<'a> {
+    Records { data: self.data, pos: self.sections[2], remaining: self.header.authority_count }
+  }
+
+  /// Iterates over the additional section
+  pub fn additional(&self) -> Records<'a> {
+    Records { data: self.data, pos: self.sections[3], remaining: self.header.additional_count }
+  }
+
+  /// Decodes the whole packet into an owned `Packet`, reading the packet data in place without copying it
+  pub fn to_owned(&self) -> Result<Packet, DrasilDNSError> {
+    Packet::parse(self.data)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::net::Ipv4Addr;
+  use crate::{header::RequestKind, packet::builder::PacketBuilder};
+
+  #[test]
+  fn packet_ref_read() {
+    let zone: Name = "example.com".parse().unwrap();
+    let host: Name = "mx.example.com".parse().unwrap();
+
+    let packet = PacketBuilder::new(3)
+      .with_request_kind(RequestKind::Response)
+      .add_question(Question { name: zone.clone(), record_type: RecordType::MX, record_class: RecordClass::IN })
+      .add_answer(Record::MX { domain: zone.clone(), priority: 10, host: host.clone(), ttl: 300, class: RecordClass::IN })
+      .add_additional(Record::A { domain: host.clone(), addr: Ipv4Addr::from_bits(0x7F000001), ttl: 300, class: RecordClass::IN })
+      .add_additional(Record::OPT {
+        udp_payload_size: 1232,
+        extended_rcode: 0,
+        version: 0,
+        dnssec_ok: true,
+        options: vec![
+          EDNSOption::Cookie { client: 100, server: Some(150) },
+          EDNSOption::KeepAlive { timeout: 20 },
+        ],
+      })
+      .build().expect("Failed to build packet");
+
+    let data = packet.to_bytes().expect("Failed to write packet");
+    let view = PacketRef::parse(&data).expect("Failed to read packet view");
+
+    assert_eq!(view.header(), &packet.header, "Header not equal");
+
+    let questions: Vec<Question> = view.questions().map(|q| q.to_owned().expect("Failed to read question")).collect();
+    assert_eq!(questions, packet.questions, "Questions not equal");
+
+    let answer = view.answers().next().expect("Answer missing");
+    assert_eq!(answer.name, zone, "Owner name not equal");
+    assert_eq!(answer.record_type, RecordType::MX, "Record type not equal");
+    assert_eq!(&answer.rdata()[..2], &[0, 10], "RDATA not equal");
+    assert_eq!(answer.name_at(2).expect("Failed to read name in RDATA"), host, "Name in RDATA not equal");
+    assert!(answer.options().is_none(), "Non-OPT record has options");
+    assert_eq!(view.authority().count(), 0, "Authority section not empty");
+
+    let opt = view.additional().nth(1).expect("OPT record missing");
+    let options: Vec<EDNSOption> = opt.options()
+      .expect("OPT record has no options")
+      .map(|o| o.and_then(|o| o.to_owned()).expect("Failed to read option"))
+      .collect();
+    assert_eq!(Some(&packet.additional[1]), opt.to_owned().expect("Failed to read record").as_ref(), "Record not equal");
+    assert_eq!(&options, match &packet.additional[1] { Record::OPT { options, .. } => options, _ => unreachable!() }, "Options not equal");
+
+    assert_eq!(view.to_owned().expect("Failed to read packet"), packet, "Packet not equal");
+    assert!(PacketRef::parse(&data[..data.len() - 1]).is_err(), "Truncated packet accepted");
+  }
+}
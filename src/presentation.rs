@@ -0,0 +1,518 @@
+
+// ===== Imports =====
+use data_encoding::{BASE32_DNSSEC, BASE64, HEXUPPER_PERMISSIVE};
+use crate::{error::DrasilDNSError, name::Name, types::RecordClass};
+// ===================
+
+/// # Token
+/// Single word of master-file text, escapes are kept as written.
+/// For quoted tokens `text` holds the content between the quotes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Token {
+  pub text: String,
+  pub quoted: bool,
+  /// Position of the first character of the token (1-based, columns count bytes)
+  pub line: usize,
+  pub column: usize,
+}
+
+/// # Entry
+/// Tokens of one master-file entry, which may span several lines when enclosed in parentheses (RFC1035 section 5.1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Entry {
+  /// Line on which the entry starts
+  pub line: usize,
+  /// Entry starts with whitespace, i.e. the owner name of the previous entry is used
+  pub blank_owner: bool,
+  pub tokens: Vec<Token>,
+}
+
+fn invalid(msg: String) -> DrasilDNSError {
+  DrasilDNSError::InvalidData { msg }
+}
+
+fn syntax(line: usize, column: usize, msg: &str) -> DrasilDNSError {
+  DrasilDNSError::Syntax { line, column, msg: msg.to_string() }
+}
+
+/// Splits master-file text into entries. Comments are dropped and parentheses only join lines together.
+pub(crate) fn entries(s: &str) -> Result<Vec<Entry>, DrasilDNSError> {
+  let bytes = s.as_bytes();
+  let mut entries = vec![];
+  let mut current: Option<Entry> = None;
+  let mut depth = 0;
+  let mut line = 1;
+  let mut line_start = true;
+  let mut line_offset = 0;
+  let mut open_paren = (0, 0);
+  let mut i = 0;
+
+  while i < bytes.len() {
+    let byte = bytes[i];
+    let at_line_start = std::mem::replace(&mut line_start, false);
+
+    match byte {
+      b'\n' => {
+        line += 1;
+        line_start = true;
+        line_offset = i + 1;
+        if depth == 0 {
+          entries.extend(current.take().filter(|e| !e.tokens.is_empty()));
+        }
+        i += 1;
+      },
+
+      b' ' | b'\t' | b'\r' => {
+        if at_line_start && depth == 0 {
+          current = Some(Entry { line, blank_owner: true, tokens: vec![] });
+        }
+        i += 1;
+      },
+
+      b';' => {
+        while i < bytes.len() && bytes[i] != b'\n' {
+          i += 1;
+        }
+      },
+
+      b'(' => {
+        if depth == 0 {
+          open_paren = (line, i - line_offset + 1);
+        }
+        depth += 1;
+        i += 1;
+      },
+
+      b')' => {
+        if depth == 0 {
+          return Err(syntax(line, i - line_offset + 1, "unbalanced closing parenthesis"));
+        }
+        depth -= 1;
+        i += 1;
+      },
+
+      _ => {
+        let entry = current.get_or_insert_with(|| Entry { line, blank_owner: false, tokens: vec![] });
+        let quoted = byte == b'"';
+        let (token_line, token_column) = (line, i - line_offset + 1);
+        let start = if quoted { i + 1 } else { i };
+        let mut end = start;
+
+        loop {
+          match bytes.get(end) {
+            None if quoted => return Err(syntax(token_line, token_column, "unterminated quoted string")),
+            None => break,
+            Some(b'\\') => {
+              end += 1;
+              if bytes.get(end) == Some(&b'\n') {
+                line += 1;
+                line_offset = end + 1;
+              }
+              end += 1;
+            },
+            Some(b'"') if quoted => break,
+            Some(b'\n') if quoted => {
+              line += 1;
+              line_offset = end + 1;
+              end += 1;
+            },
+            Some(b' ' | b'\t' | b'\r' | b'\n' | b';' | b'(' | b')' | b'"') if !quoted => break,
+            Some(_) => end += 1,
+          }
+        }
+
+        let end = end.min(bytes.len());
+        entry.tokens.push(Token { text: s[start..end].to_string(), quoted, line: token_line, column: token_column });
+        i = if quoted { end + 1 } else { end };
+      },
+    }
+  }
+
+  if depth != 0 {
+    return Err(syntax(open_paren.0, open_paren.1, "unbalanced opening parenthesis"));
+  }
+  entries.extend(current.take().filter(|e| !e.tokens.is_empty()));
+
+  Ok(entries)
+}
+
+/// # Tokens
+/// Cursor over the tokens of an entry, used while parsing its fields one after another.
+pub(crate) struct Tokens<'a> {
+  tokens: &'a [Token],
+  pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+  pub fn new(tokens: &'a [Token]) -> Self {
+    Self { tokens, pos: 0 }
+  }
+
+  pub fn peek(&self) -> Option<&'a Token> {
+    self.tokens.get(self.pos)
+  }
+
+  /// Returns the token returned last, used to locate errors
+  pub fn last(&self) -> Option<&'a Token> {
+    self.pos.checked_sub(1).and_then(|pos| self.tokens.get(pos))
+  }
+
+  /// Returns the next token, failing with a message naming the missing field
+  pub fn next(&mut self, field: &str) -> Result<&'a Token, DrasilDNSError> {
+    let token = self.peek().ok_or_else(|| invalid(format!("missing {}", field)))?;
+    self.pos += 1;
+    Ok(token)
+  }
+
+  /// Returns all remaining tokens
+  pub fn rest(&mut self) -> &'a [Token] {
+    let rest = &self.tokens[self.pos..];
+    self.pos = self.tokens.len();
+    rest
+  }
+
+  /// Fails if there are tokens which were not consumed
+  pub fn finish(&mut self) -> Result<(), DrasilDNSError> {
+    match self.peek() {
+      Some(token) => {
+        // consumed so that `last` points at it when reporting the error position
+        self.pos += 1;
+        Err(invalid(format!("unexpected trailing data \"{}\"", token.text)))
+      },
+      None => Ok(()),
+    }
+  }
+
+  /// Parses the next token as a decimal number
+  pub fn number<T: std::str::FromStr>(&mut self, field: &str) -> Result<T, DrasilDNSError> {
+    let token = self.next(field)?;
+    token.text.parse().map_err(|_| invalid(format!("invalid {} \"{}\"", field, token.text)))
+  }
+
+  /// Parses the next token as a TTL (or another time value), see `parse_ttl`
+  pub fn ttl(&mut self, field: &str) -> Result<u32, DrasilDNSError> {
+    let token = self.next(field)?;
+    parse_ttl(&token.text).map_err(|_| invalid(format!("invalid {} \"{}\"", field, token.text)))
+  }
+
+  /// Reads the optional TTL and class fields of a record, which may come in either order (RFC1035 section 5.1)
+  pub fn ttl_and_class(&mut self) -> (Option<u32>, Option<RecordClass>) {
+    let mut ttl = None;
+    let mut class = None;
+
+    while let Some(token) = self.peek() {
+      if let (None, Ok(v)) = (ttl, parse_ttl(&token.text)) {
+        ttl = Some(v);
+      } else if let (None, Ok(v)) = (class, token.text.parse::<RecordClass>()) {
+        class = Some(v);
+      } else {
+        break;
+      }
+      self.pos += 1;
+    }
+
+    (ttl, class)
+  }
+
+  /// Parses the next token as a domain name, relative names are completed with `origin` (`@` stands for the origin itself)
+  pub fn name(&mut self, field: &str, origin: Option<&Name>) -> Result<Name, DrasilDNSError> {
+    parse_name(&self.next(field)?.text, origin)
+  }
+
+  /// Concatenates the remaining tokens, used for hex and base64 fields which may contain whitespace
+  pub fn concat(&mut self) -> String {
+    self.rest().iter().map(|t| t.text.as_str()).collect()
+  }
+}
+
+/// Parses a domain name, relative names are completed with `origin` (`@` stands for the origin itself).
+/// Without an origin every name is treated as fully qualified.
+pub(crate) fn parse_name(text: &str, origin: Option<&Name>) -> Result<Name, DrasilDNSError> {
+  let Some(origin) = origin else {
+    if text == "@" {
+      return Err(invalid("\"@\" used without an origin".to_string()));
+    }
+    return text.parse();
+  };
+
+  if text == "@" {
+    return Ok(origin.clone());
+  }
+
+  // a trailing dot makes the name absolute, unless the dot itself is escaped
+  if let Some(rest) = text.strip_suffix('.') {
+    let backslashes = rest.len() - rest.trim_end_matches('\\').len();
+    if backslashes.is_multiple_of(2) {
+      return text.parse();
+    }
+  }
+
+  let relative: Name = text.parse()?;
+  Name::from_labels(relative.labels().chain(origin.labels()))
+}
+
+/// Parses a TTL, either as seconds or with units like `1h30m` (`w`, `d`, `h`, `m` and `s`, in any case)
+pub(crate) fn parse_ttl(text: &str) -> Result<u32, DrasilDNSError> {
+  let err = || invalid(format!("invalid TTL \"{}\"", text));
+
+  if let Ok(ttl) = text.parse() {
+    return Ok(ttl);
+  }
+
+  let mut total: u32 = 0;
+  let mut value: Option<u32> = None;
+
+  for c in text.chars() {
+    if let Some(d) = c.to_digit(10) {
+      value = Some(value.unwrap_or(0).checked_mul(10).and_then(|v| v.checked_add(d)).ok_or_else(err)?);
+      continue;
+    }
+
+    let unit = match c.to_ascii_lowercase() {
+      'w' => 604800,
+      'd' => 86400,
+      'h' => 3600,
+      'm' => 60,
+      's' => 1,
+      _ => return Err(err()),
+    };
+    let seconds = value.take().ok_or_else(err)?.checked_mul(unit).ok_or_else(err)?;
+    total = total.checked_add(seconds).ok_or_else(err)?;
+  }
+
+  // a trailing number without unit counts as seconds, but only after some unit
+  match value {
+    Some(v) => total.checked_add(v).ok_or_else(err),
+    None if text.is_empty() => Err(err()),
+    None => Ok(total),
+  }
+}
+
+/// Writes a character-string (RFC1035 section 5.1) in quotes, escaping quotes, backslashes and non-printable octets
+pub(crate) fn fmt_character_string(data: &[u8]) -> String {
+  let mut s = String::with_capacity(data.len() + 2);
+  s.push('"');
+
+  for &byte in data {
+    match byte {
+      b'"' | b'\\' => {
+        s.push('\\');
+        s.push(byte as char);
+      },
+      0x20..=0x7E => s.push(byte as char),
+      _ => s.push_str(&format!("\\{:03}", byte)),
+    }
+  }
+
+  s.push('"');
+  s
+}
+
+/// Writes the data as a single unquoted word, escaping whitespace, special and non-printable octets.
+/// Commas are escaped as well when `in_list` is set, so that the data can be an item of a comma separated list.
+pub(crate) fn fmt_escaped(data: &[u8], in_list: bool) -> String {
+  let mut s = String::with_capacity(data.len());
+
+  for &byte in data {
+    match byte {
+      b'"' | b'\\' => {
+        s.push('\\');
+        s.push(byte as char);
+      },
+      b';' | b'(' | b')' => s.push_str(&format!("\\{:03}", byte)),
+      b',' if in_list => s.push_str(&format!("\\{:03}", byte)),
+      0x21..=0x7E => s.push(byte as char),
+      _ => s.push_str(&format!("\\{:03}", byte)),
+    }
+  }
+
+  s
+}
+
+/// Splits a comma separated list, commas escaped with a backslash don't separate items
+pub(crate) fn split_list(text: &str) -> Vec<&str> {
+  let mut items = vec![];
+  let mut start = 0;
+  let mut escaped = false;
+
+  for (i, byte) in text.bytes().enumerate() {
+    match byte {
+      _ if escaped => escaped = false,
+      b'\\' => escaped = true,
+      b',' => {
+        items.push(&text[start..i]);
+        start = i + 1;
+      },
+      _ => {},
+    }
+  }
+
+  items.push(&text[start..]);
+  items
+}
+
+/// Resolves the `\X` and `\DDD` escapes of a token
+pub(crate) fn unescape(text: &str) -> Result<Vec<u8>, DrasilDNSError> {
+  let mut data = Vec::with_capacity(text.len());
+  let mut bytes = text.bytes();
+
+  while let Some(byte) = bytes.next() {
+    if byte != b'\\' {
+      data.push(byte);
+      continue;
+    }
+
+    match bytes.next() {
+      Some(d) if d.is_ascii_digit() => {
+        let mut val = (d - b'0') as u16;
+        for _ in 0..2 {
+          match bytes.next() {
+            Some(d) if d.is_ascii_digit() => val = val * 10 + (d - b'0') as u16,
+            _ => return Err(invalid(format!("incomplete \\DDD escape in \"{}\"", text))),
+          }
+        }
+        if val > 255 {
+          return Err(invalid(format!("out of range \\DDD escape in \"{}\"", text)));
+        }
+        data.push(val as u8);
+      },
+      Some(c) => data.push(c),
+      None => return Err(invalid(format!("dangling escape in \"{}\"", text))),
+    }
+  }
+
+  Ok(data)
+}
+
+/// Parses a character-string token, which may be quoted or not
+pub(crate) fn parse_character_string(token: &Token) -> Result<Vec<u8>, DrasilDNSError> {
+  let data = unescape(&token.text)?;
+  if data.len() > 255 {
+    return Err(DrasilDNSError::CharacterStringTooLarge { size: data.len() });
+  }
+  Ok(data)
+}
+
+/// Writes the data as upper-case hex
+pub(crate) fn fmt_hex(data: &[u8]) -> String {
+  data_encoding::HEXUPPER.encode(data)
+}
+
+/// Parses hex in either case
+pub(crate) fn parse_hex(text: &str) -> Result<Vec<u8>, DrasilDNSError> {
+  HEXUPPER_PERMISSIVE.decode(text.as_bytes()).map_err(|_| invalid(format!("invalid hex \"{}\"", text)))
+}
+
+pub(crate) fn fmt_base64(data: &[u8]) -> String {
+  BASE64.encode(data)
+}
+
+pub(crate) fn parse_base64(text: &str) -> Result<Vec<u8>, DrasilDNSError> {
+  BASE64.decode(text.as_bytes()).map_err(|_| invalid(format!("invalid base64 \"{}\"", text)))
+}
+
+/// Writes the data in Base 32 Encoding with Extended Hex Alphabet without padding, as used by NSEC3 (RFC5155 section 3.3)
+pub(crate) fn fmt_base32hex(data: &[u8]) -> String {
+  BASE32_DNSSEC.encode(data)
+}
+
+pub(crate) fn parse_base32hex(text: &str) -> Result<Vec<u8>, DrasilDNSError> {
+  BASE32_DNSSEC.decode(text.to_ascii_lowercase().as_bytes()).map_err(|_| invalid(format!("invalid base32hex \"{}\"", text)))
+}
+
+/// Writes a DNSSEC timestamp in the `YYYYMMDDHHmmSS` form (RFC4034 section 3.2)
+pub(crate) fn fmt_timestamp(secs: u32) -> String {
+  let days = (secs / 86400) as i64;
+  let rem = secs % 86400;
+
+  // civil date from days since 1970-01-01, proleptic Gregorian calendar
+  let z = days + 719468;
+  let era = z / 146097;
+  let doe = z - era * 146097;
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  format!("{:04}{:02}{:02}{:02}{:02}{:02}", year, month, day, rem / 3600, (rem / 60) % 60, rem % 60)
+}
+
+/// Parses a DNSSEC timestamp, either in the `YYYYMMDDHHmmSS` form or as seconds since the epoch
+pub(crate) fn parse_timestamp(text: &str) -> Result<u32, DrasilDNSError> {
+  let err = || invalid(format!("invalid timestamp \"{}\"", text));
+
+  if text.len() != 14 {
+    return text.parse().map_err(|_| err());
+  }
+  if !text.bytes().all(|b| b.is_ascii_digit()) {
+    return Err(err());
+  }
+
+  let field = |range: std::ops::Range<usize>| text[range].parse::<i64>().unwrap_or_default(); // digits were checked above
+  let (year, month, day) = (field(0..4), field(4..6), field(6..8));
+  let (hour, minute, second) = (field(8..10), field(10..12), field(12..14));
+
+  if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+    return Err(err());
+  }
+
+  // days since 1970-01-01 from a civil date, inverse of `fmt_timestamp`
+  let y = if month <= 2 { year - 1 } else { year };
+  let era = y / 400;
+  let yoe = y - era * 400;
+  let mp = if month > 2 { month - 3 } else { month + 9 };
+  let doy = (153 * mp + 2) / 5 + day - 1;
+  let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+  let days = era * 146097 + doe - 719468;
+
+  u32::try_from(days * 86400 + hour * 3600 + minute * 60 + second).map_err(|_| err())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn master_file_tokens() {
+    let text = "a.example. 300 IN TXT \"hello world\" ( \"multi\"\n  line ) ; comment\n\n  IN A 10.0.0.1\n";
+    let entries = entries(text).expect("Failed to split entries");
+
+    assert_eq!(entries.len(), 2, "Incorrect number of entries");
+    let texts: Vec<&str> = entries[0].tokens.iter().map(|t| t.text.as_str()).collect();
+    assert_eq!(texts, ["a.example.", "300", "IN", "TXT", "hello world", "multi", "line"]);
+    assert!(entries[0].tokens[4].quoted && !entries[0].tokens[6].quoted, "Quoting not recorded");
+    assert!(!entries[0].blank_owner && entries[1].blank_owner, "Blank owner not detected");
+    assert_eq!(entries[1].line, 4, "Incorrect line number");
+
+    assert_eq!((entries[1].tokens[1].line, entries[1].tokens[1].column), (4, 6), "Incorrect token position");
+    assert!(matches!(super::entries("a\n b ( c"), Err(DrasilDNSError::Syntax { line: 2, column: 4, .. })), "Unbalanced parentheses accepted");
+    assert!(matches!(super::entries("a \"b"), Err(DrasilDNSError::Syntax { line: 1, column: 3, .. })), "Unterminated quote accepted");
+
+    assert_eq!(parse_ttl("3600").unwrap(), 3600);
+    assert_eq!(parse_ttl("1h30M").unwrap(), 5400);
+    assert_eq!(parse_ttl("1w1d1h1m1s").unwrap(), 694861);
+    assert!(parse_ttl("h").is_err() && parse_ttl("1x").is_err() && parse_ttl("").is_err(), "Invalid TTL accepted");
+    assert!(parse_ttl("10000w").is_err(), "Overflowing TTL accepted");
+
+    let data = b"a\"b\\c\x00\xFF".to_vec();
+    let text = fmt_character_string(&data);
+    assert_eq!(text, "\"a\\\"b\\\\c\\000\\255\"");
+    assert_eq!(unescape(&text[1..(text.len() - 1)]).unwrap(), data, "Character-string not equal after display+parse");
+
+    let origin: Name = "example.com".parse().unwrap();
+    assert_eq!(parse_name("www", Some(&origin)).unwrap(), "www.example.com".parse().unwrap());
+    assert_eq!(parse_name("www.", Some(&origin)).unwrap(), "www".parse().unwrap());
+    assert_eq!(parse_name("@", Some(&origin)).unwrap(), origin);
+    assert_eq!(parse_name("a\\.", Some(&origin)).unwrap().label_count(), 3, "Escaped dot treated as absolute");
+    assert!(parse_name("@", None).is_err(), "@ accepted without origin");
+
+    // example from RFC4034 section 3.3
+    assert_eq!(parse_timestamp("20030322173103").unwrap(), 1048354263);
+    assert_eq!(fmt_timestamp(1048354263), "20030322173103");
+    assert_eq!(fmt_timestamp(u32::MAX), "21060207062815");
+    assert_eq!(parse_timestamp("21060207062815").unwrap(), u32::MAX);
+    assert!(parse_timestamp("21060207062816").is_err(), "Timestamp past 2106 accepted");
+  }
+}
@@ -1,13 +1,16 @@
 
 // ===== Imports =====
-use crate::{buffer::Buffer, types::{RecordClass, RecordType}, error::DrasilDNSError};
+use std::{fmt, str::FromStr};
+use crate::{buffer::Buffer, name::Name, presentation::{self, Tokens}, types::{RecordClass, RecordType}, error::DrasilDNSError};
 // ===================
 
 /// # Question
 /// Struct representing a question record
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Question {
-  pub name: Vec<String>,
+  pub name: Name,
   pub record_type: RecordType,
   pub record_class: RecordClass,
 }
@@ -24,15 +27,43 @@ impl Question {
   }
 
   pub(crate) fn write_bytes(&self, buff: &mut Buffer) -> Result<(), DrasilDNSError> {
-    let mut b = Buffer::with_capacity(0);
-    b.set_expandable(true);
+    buff.write_transaction(|buff| {
+      buff.write_labels_compressed(&self.name)?;
+      buff.write_u16(self.record_type.into())?;
+      buff.write_u16(self.record_class.into())?;
+      Ok(())
+    })
+  }
+}
+
+impl fmt::Display for Question {
+  /// Writes the question as `<name> <class> <type>`
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {} {}", self.name, self.record_class, self.record_type)
+  }
+}
+
+impl FromStr for Question {
+  type Err = DrasilDNSError;
+
+  /// Parses a question written as `<name> [class] <type>`, the class defaults to `IN`
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let entries = presentation::entries(s)?;
+    let [entry] = entries.as_slice() else {
+      return Err(DrasilDNSError::InvalidData { msg: format!("expected a single question, found {}", entries.len()) });
+    };
 
-    b.write_labels(&self.name)?;
-    b.write_u16(self.record_type.into())?;
-    b.write_u16(self.record_class.into())?;
+    let mut t = Tokens::new(&entry.tokens);
+    let name = t.name("name", None)?;
 
-    buff.write_buffer(&b)?;
-    Ok(())
+    let record_class = match entry.tokens.len() {
+      3 => t.next("class")?.text.parse()?,
+      _ => RecordClass::IN,
+    };
+    let record_type = t.next("record type")?.text.parse()?;
+    t.finish()?;
+
+    Ok(Self { name, record_type, record_class })
   }
 }
 
@@ -43,7 +74,7 @@ mod tests {
   #[test]
   fn question_rw() {
     let question = Question {
-      name: vec!["google".to_string(), "com".to_string()],
+      name: "google.com".parse().unwrap(),
       record_type: RecordType::A,
       record_class: RecordClass::IN,
     };
@@ -59,4 +90,23 @@ mod tests {
 
     assert_eq!(question, question_after_read, "Question not equal after write+read");
   }
+
+  #[test]
+  fn question_presentation() {
+    let question = Question {
+      name: "google.com".parse().unwrap(),
+      record_type: RecordType::Unknown(65280),
+      record_class: RecordClass::IN,
+    };
+
+    assert_eq!(question.to_string(), "google.com. IN TYPE65280", "Question not displayed correctly");
+    assert_eq!(question.to_string().parse::<Question>().unwrap(), question, "Question not equal after display+parse");
+    assert_eq!("google.com type65280".parse::<Question>().unwrap(), question, "Question without class not parsed");
+    assert!("google.com IN".parse::<Question>().is_err(), "Question without type accepted");
+
+    for text in ["version.bind. CH TXT", "google.com. ANY ANY", "google.com. IN AXFR", "google.com. HS IXFR"] {
+      let question: Question = text.parse().expect("Failed to parse question");
+      assert_eq!(question.to_string(), text, "Question not equal after parse+display");
+    }
+  }
 }
\ No newline at end of file
@@ -2,19 +2,39 @@
 /// Provides eDNS types
 pub mod edns;
 
+/// Provides SVCB/HTTPS service parameter types
+pub mod svcb;
+
+/// Provides CAA property types
+pub mod caa;
+
+/// Provides the generic RDATA presentation format (RFC3597)
+pub mod generic;
+
+/// Provides the `RRset` type grouping records by owner name, class and type
+pub mod rrset;
+
+mod presentation;
+
 // ===== Imports =====
 use std::{collections::HashSet, net::{Ipv4Addr, Ipv6Addr}};
-use crate::{buffer::Buffer, error::DrasilDNSError, record::edns::EDNSOption, types::{dnssec::{DNSSECAlgorithm, DNSSECDigestType}, RecordClass, RecordType}};
+use crate::{buffer::Buffer, error::DrasilDNSError, name::Name, record::{caa::CAAValue, edns::EDNSOption, svcb::SvcParam}, types::{dane::{SSHFPAlgorithm, SSHFPFingerprintType, TLSACertUsage, TLSAMatchingType, TLSASelector}, dnssec::{DNSSECAlgorithm, DNSSECDigestType}, RecordClass, RecordType}};
 // ===================
 
+static ROOT: Name = Name::root();
+
 /// # Record
 /// Enum for representing various kinds of DNS records that exist.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Record {
+  /// Record of a type without a typed representation, its RDATA is kept opaque (RFC3597).
+  /// `len` must match the length of `data`.
   Unknown {
-    domain: Vec<String>,
+    domain: Name,
     ttl: u32,
-    len: u32,
+    len: u16,
     record_type: u16,
     class: RecordClass,
     data: Vec<u8>,
@@ -22,7 +42,7 @@ pub enum Record {
 
   /// `A` record maps domain names to IPv4 addresses
   A {
-    domain: Vec<String>,
+    domain: Name,
     addr: Ipv4Addr,
     ttl: u32,
     class: RecordClass,
@@ -30,37 +50,116 @@ pub enum Record {
 
   /// `NS` record tells which nameserver is responsible for the asked domain
   NS {
-    domain: Vec<String>,
-    host: Vec<String>,
+    domain: Name,
+    host: Name,
     ttl: u32,
     class: RecordClass,
   }, // 2
 
   /// `CNAME` record maps one domain name to another one
   CNAME {
-    domain: Vec<String>,
-    host: Vec<String>,
+    domain: Name,
+    host: Name,
     ttl: u32,
     class: RecordClass,
   }, // 5
 
+  /// `SOA` (Start Of Authority) record marks the start of a zone and holds its parameters
+  SOA {
+    domain: Name,
+    mname: Name,
+    rname: Name,
+    serial: u32,
+    refresh: u32,
+    retry: u32,
+    expire: u32,
+    minimum: u32,
+    ttl: u32,
+    class: RecordClass,
+  }, // 6
+
+  /// `NULL` record holds arbitrary data
+  NULL {
+    domain: Name,
+    data: Vec<u8>,
+    ttl: u32,
+    class: RecordClass,
+  }, // 10
+
+  /// `PTR` record points to another domain name, mostly used for reverse lookups
+  PTR {
+    domain: Name,
+    host: Name,
+    ttl: u32,
+    class: RecordClass,
+  }, // 12
+
+  /// `HINFO` record describes the CPU and operating system of a host
+  HINFO {
+    domain: Name,
+    cpu: Vec<u8>,
+    os: Vec<u8>,
+    ttl: u32,
+    class: RecordClass,
+  }, // 13
+
   /// `MX` (Mail Exchange) record specifies where to deliver emails for a specific domain
   MX {
-    domain: Vec<String>,
+    domain: Name,
     priority: u16,
-    host: Vec<String>,
+    host: Name,
     ttl: u32,
     class: RecordClass,
   }, // 15
 
+  /// `TXT` record holds one or more character-strings of descriptive text
+  TXT {
+    domain: Name,
+    strings: Vec<Vec<u8>>,
+    ttl: u32,
+    class: RecordClass,
+  }, // 16
+
   /// `AAAA` record maps domains to IPv6 addresses
   AAAA {
-    domain: Vec<String>,
+    domain: Name,
     addr: Ipv6Addr,
     ttl: u32,
     class: RecordClass,
   }, // 28
 
+  /// `SRV` record specifies the location of servers for a specific service (RFC2782)
+  SRV {
+    domain: Name,
+    priority: u16,
+    weight: u16,
+    port: u16,
+    target: Name,
+    ttl: u32,
+    class: RecordClass,
+  }, // 33
+
+  /// `NAPTR` (Naming Authority Pointer) record holds rewrite rules used for dynamic delegation discovery (RFC3403)
+  NAPTR {
+    domain: Name,
+    order: u16,
+    preference: u16,
+    flags: Vec<u8>,
+    services: Vec<u8>,
+    regexp: Vec<u8>,
+    replacement: Name,
+    ttl: u32,
+    class: RecordClass,
+  }, // 35
+
+  /// `DNAME` record redirects a whole subtree of the domain name space to another domain (RFC6672)
+  DNAME {
+    domain: Name,
+    target: Name,
+    ttl: u32,
+    class: RecordClass,
+  }, // 39
+
   OPT {
     udp_payload_size: u16,
     extended_rcode: u8,
@@ -70,7 +169,7 @@ pub enum Record {
   }, // 41
 
   DS {
-    domain: Vec<String>,
+    domain: Name,
     class: RecordClass,
     ttl: u32,
     key_tag: u16,
@@ -79,8 +178,18 @@ pub enum Record {
     digest: Vec<u8>,
   }, // 43
 
+  /// `SSHFP` record holds the fingerprint of a host's SSH public key (RFC4255)
+  SSHFP {
+    domain: Name,
+    class: RecordClass,
+    ttl: u32,
+    algorithm: SSHFPAlgorithm,
+    fingerprint_type: SSHFPFingerprintType,
+    fingerprint: Vec<u8>,
+  }, // 44
+
   RRSIG {
-    domain: Vec<String>,
+    domain: Name,
     class: RecordClass,
     ttl: u32,
     type_covered: u16,
@@ -90,35 +199,41 @@ pub enum Record {
     signature_expiration: u32,
     signature_inception: u32,
     key_tag: u16,
-    signer_name: Vec<String>,
+    signer_name: Name,
     signature: Vec<u8>,
   }, // 46
 
   NSEC {
-    domain: Vec<String>,
+    domain: Name,
     class: RecordClass,
     ttl: u32,
-    next_domain_name: Vec<String>,
+    next_domain_name: Name,
     record_types: HashSet<RecordType>,
   }, // 47
 
+  /// `DNSKEY` record holds a public key of the zone (RFC4034 section 2)
+  ///
+  /// `flags` is the raw flags field, the Zone Key flag is `flags & 0x0100`, the Secure Entry Point flag is `flags & 0x0001`
+  /// and the REVOKE flag (RFC5011 section 3) is `flags & 0x0080`
   DNSKEY {
-    domain: Vec<String>,
+    domain: Name,
     class: RecordClass,
     ttl: u32,
-    is_secure_entry_point: bool,
-    is_zone_key: bool,
+    flags: u16,
     protocol: u8,
     algorithm: DNSSECAlgorithm,
     public_key: Vec<u8>,
   }, // 48
 
+  /// `NSEC3` record links the hashed owner names of the zone (RFC5155 section 3)
+  ///
+  /// `flags` is the raw flags octet, the Opt-Out flag is `flags & 0x01` (see `Record::opt_out`) and the other bits are unassigned
   NSEC3 {
-    domain: Vec<String>,
+    domain: Name,
     class: RecordClass,
     ttl: u32,
     hash_algorithm: u8,
-    opt_out: bool,
+    flags: u8,
     iterations: u16,
     salt_length: u8,
     salt: Vec<u8>,
@@ -127,8 +242,9 @@ pub enum Record {
     record_types: HashSet<RecordType>,
   }, // 50
 
+  /// Authoritative servers must ignore it if `flags` is not 0 (RFC5155 section 4.1.2)
   NSEC3PARAM {
-    domain: Vec<String>,
+    domain: Name,
     class: RecordClass,
     ttl: u32,
     hash_algorithm: u8,
@@ -137,52 +253,285 @@ pub enum Record {
     salt_length: u8,
     salt: Vec<u8>,
   }, // 51
+
+  /// `TLSA` record associates a TLS server certificate or public key with the domain name (RFC6698)
+  TLSA {
+    domain: Name,
+    class: RecordClass,
+    ttl: u32,
+    cert_usage: TLSACertUsage,
+    selector: TLSASelector,
+    matching_type: TLSAMatchingType,
+    data: Vec<u8>,
+  }, // 52
+
+  /// `SMIMEA` record associates an S/MIME certificate with an email address (RFC8162)
+  SMIMEA {
+    domain: Name,
+    class: RecordClass,
+    ttl: u32,
+    cert_usage: TLSACertUsage,
+    selector: TLSASelector,
+    matching_type: TLSAMatchingType,
+    data: Vec<u8>,
+  }, // 53
+
+  /// `OPENPGPKEY` record holds an OpenPGP transferable public key for an email address (RFC7929)
+  OPENPGPKEY {
+    domain: Name,
+    class: RecordClass,
+    ttl: u32,
+    public_key: Vec<u8>,
+  }, // 61
+
+  /// `SVCB` (Service Binding) record provides alternative endpoints and connection parameters for a service (RFC9460)
+  SVCB {
+    domain: Name,
+    priority: u16,
+    target: Name,
+    params: Vec<SvcParam>,
+    ttl: u32,
+    class: RecordClass,
+  }, // 64
+
+  /// `HTTPS` record is the SVCB record specialised for HTTP origins (RFC9460)
+  HTTPS {
+    domain: Name,
+    priority: u16,
+    target: Name,
+    params: Vec<SvcParam>,
+    ttl: u32,
+    class: RecordClass,
+  }, // 65
+
+  /// `URI` record maps a domain name to a URI (RFC7553)
+  URI {
+    domain: Name,
+    priority: u16,
+    weight: u16,
+    target: Vec<u8>,
+    ttl: u32,
+    class: RecordClass,
+  }, // 256
+
+  /// `CAA` (Certification Authority Authorization) record restricts which CAs may issue certificates for the domain (RFC8659)
+  ///
+  /// `flags` is the raw flags octet, the issuer critical flag is `flags & 0x80 != 0` and the other bits are reserved (RFC8659 section 4.1).
+  /// `tag` and `value` are the property as found on the wire and are written back unchanged, `property` is their parsed form
+  CAA {
+    domain: Name,
+    class: RecordClass,
+    ttl: u32,
+    flags: u8,
+    tag: Vec<u8>,
+    value: Vec<u8>,
+    property: CAAValue,
+  }, // 257
 }
 
 impl Record {
+  /// Returns the owner name of the record (root for `OPT`)
+  pub fn domain(&self) -> &Name {
+    match self {
+      Self::OPT { .. } => &ROOT,
+      Self::Unknown { domain, .. }
+      | Self::A { domain, .. }
+      | Self::NS { domain, .. }
+      | Self::CNAME { domain, .. }
+      | Self::SOA { domain, .. }
+      | Self::NULL { domain, .. }
+      | Self::PTR { domain, .. }
+      | Self::HINFO { domain, .. }
+      | Self::MX { domain, .. }
+      | Self::TXT { domain, .. }
+      | Self::AAAA { domain, .. }
+      | Self::SRV { domain, .. }
+      | Self::NAPTR { domain, .. }
+      | Self::DNAME { domain, .. }
+      | Self::DS { domain, .. }
+      | Self::SSHFP { domain, .. }
+      | Self::RRSIG { domain, .. }
+      | Self::NSEC { domain, .. }
+      | Self::DNSKEY { domain, .. }
+      | Self::NSEC3 { domain, .. }
+      | Self::NSEC3PARAM { domain, .. }
+      | Self::TLSA { domain, .. }
+      | Self::SMIMEA { domain, .. }
+      | Self::OPENPGPKEY { domain, .. }
+      | Self::SVCB { domain, .. }
+      | Self::HTTPS { domain, .. }
+      | Self::URI { domain, .. }
+      | Self::CAA { domain, .. } => domain,
+    }
+  }
+
+  /// Returns the type of the record
+  pub fn record_type(&self) -> RecordType {
+    match self {
+      Self::Unknown { record_type, .. } => RecordType::from(*record_type),
+      Self::A { .. } => RecordType::A,
+      Self::NS { .. } => RecordType::NS,
+      Self::CNAME { .. } => RecordType::CNAME,
+      Self::SOA { .. } => RecordType::SOA,
+      Self::NULL { .. } => RecordType::NULL,
+      Self::PTR { .. } => RecordType::PTR,
+      Self::HINFO { .. } => RecordType::HINFO,
+      Self::MX { .. } => RecordType::MX,
+      Self::TXT { .. } => RecordType::TXT,
+      Self::AAAA { .. } => RecordType::AAAA,
+      Self::SRV { .. } => RecordType::SRV,
+      Self::NAPTR { .. } => RecordType::NAPTR,
+      Self::DNAME { .. } => RecordType::DNAME,
+      Self::OPT { .. } => RecordType::OPT,
+      Self::DS { .. } => RecordType::DS,
+      Self::SSHFP { .. } => RecordType::SSHFP,
+      Self::RRSIG { .. } => RecordType::RRSIG,
+      Self::NSEC { .. } => RecordType::NSEC,
+      Self::DNSKEY { .. } => RecordType::DNSKEY,
+      Self::NSEC3 { .. } => RecordType::NSEC3,
+      Self::NSEC3PARAM { .. } => RecordType::NSEC3PARAM,
+      Self::TLSA { .. } => RecordType::TLSA,
+      Self::SMIMEA { .. } => RecordType::SMIMEA,
+      Self::OPENPGPKEY { .. } => RecordType::OPENPGPKEY,
+      Self::SVCB { .. } => RecordType::SVCB,
+      Self::HTTPS { .. } => RecordType::HTTPS,
+      Self::URI { .. } => RecordType::URI,
+      Self::CAA { .. } => RecordType::CAA,
+    }
+  }
+
+  /// Returns the class of the record (for `OPT` this is the UDP payload size, as stored on the wire)
+  pub fn class(&self) -> RecordClass {
+    match self {
+      Self::OPT { udp_payload_size, .. } => RecordClass::from(*udp_payload_size),
+      Self::Unknown { class, .. }
+      | Self::A { class, .. }
+      | Self::NS { class, .. }
+      | Self::CNAME { class, .. }
+      | Self::SOA { class, .. }
+      | Self::NULL { class, .. }
+      | Self::PTR { class, .. }
+      | Self::HINFO { class, .. }
+      | Self::MX { class, .. }
+      | Self::TXT { class, .. }
+      | Self::AAAA { class, .. }
+      | Self::SRV { class, .. }
+      | Self::NAPTR { class, .. }
+      | Self::DNAME { class, .. }
+      | Self::DS { class, .. }
+      | Self::SSHFP { class, .. }
+      | Self::RRSIG { class, .. }
+      | Self::NSEC { class, .. }
+      | Self::DNSKEY { class, .. }
+      | Self::NSEC3 { class, .. }
+      | Self::NSEC3PARAM { class, .. }
+      | Self::TLSA { class, .. }
+      | Self::SMIMEA { class, .. }
+      | Self::OPENPGPKEY { class, .. }
+      | Self::SVCB { class, .. }
+      | Self::HTTPS { class, .. }
+      | Self::URI { class, .. }
+      | Self::CAA { class, .. } => *class,
+    }
+  }
+
+  /// Returns the TTL of the record (for `OPT` this packs the extended RCODE, version and flags, as stored on the wire)
+  pub fn ttl(&self) -> u32 {
+    match self {
+      Self::OPT { extended_rcode, version, dnssec_ok, .. } => {
+        u32::from_be_bytes([*extended_rcode, *version, if *dnssec_ok { 0b1000_0000 } else { 0 }, 0])
+      },
+      Self::Unknown { ttl, .. }
+      | Self::A { ttl, .. }
+      | Self::NS { ttl, .. }
+      | Self::CNAME { ttl, .. }
+      | Self::SOA { ttl, .. }
+      | Self::NULL { ttl, .. }
+      | Self::PTR { ttl, .. }
+      | Self::HINFO { ttl, .. }
+      | Self::MX { ttl, .. }
+      | Self::TXT { ttl, .. }
+      | Self::AAAA { ttl, .. }
+      | Self::SRV { ttl, .. }
+      | Self::NAPTR { ttl, .. }
+      | Self::DNAME { ttl, .. }
+      | Self::DS { ttl, .. }
+      | Self::SSHFP { ttl, .. }
+      | Self::RRSIG { ttl, .. }
+      | Self::NSEC { ttl, .. }
+      | Self::DNSKEY { ttl, .. }
+      | Self::NSEC3 { ttl, .. }
+      | Self::NSEC3PARAM { ttl, .. }
+      | Self::TLSA { ttl, .. }
+      | Self::SMIMEA { ttl, .. }
+      | Self::OPENPGPKEY { ttl, .. }
+      | Self::SVCB { ttl, .. }
+      | Self::HTTPS { ttl, .. }
+      | Self::URI { ttl, .. }
+      | Self::CAA { ttl, .. } => *ttl,
+    }
+  }
+
+  /// Returns the RDATA of the record in wire format, names inside it are never compressed
+  pub fn rdata(&self) -> Result<Vec<u8>, DrasilDNSError> {
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
+    self.write_rdata(&mut b)?;
+    Ok(b.into())
+  }
+
+  /// Synthesizes the CNAME record for a name below the owner of a `DNAME` record (RFC6672 section 3.3).
+  /// The owner suffix of `qname` is replaced by the DNAME target, while TTL and class are taken from the DNAME.
+  /// Returns `None` if this is not a `DNAME` record or `qname` is not strictly below its owner,
+  /// and fails with `DrasilDNSError::NameTooLarge` if the substituted name would be too long (YXDOMAIN).
+  pub fn synthesize_cname(&self, qname: &Name) -> Result<Option<Record>, DrasilDNSError> {
+    let Self::DNAME { domain, target, ttl, class } = self else {
+      return Ok(None);
+    };
+
+    if qname.label_count() <= domain.label_count() || !qname.is_subdomain_of(domain) {
+      return Ok(None);
+    }
+
+    let prefix = qname.label_count() - domain.label_count();
+    let host = Name::from_labels(qname.labels().take(prefix).chain(target.labels()))?;
+
+    Ok(Some(Self::CNAME { domain: qname.clone(), host, ttl: *ttl, class: *class }))
+  }
+
   pub(crate) fn parse(buff: &mut Buffer) -> Result<Option<Self>, DrasilDNSError> {
     buff.read_transaction(|buff| {
       let (_, domain) = buff.read_labels(true)?;
       let record_type = RecordType::from(buff.read_u16()?);
       let class = RecordClass::from(buff.read_u16()?);
       let ttl = buff.read_u32()?;
-      let len = buff.read_u32()?;
+      let len = buff.read_u16()?;
+      let end = buff.pos() + len as usize;
 
-      Ok(Some(match record_type {
-        RecordType::Unknown(v) => {
+      let record = match record_type {
+        // query types have no RDATA format of their own
+        RecordType::Unknown(_) | RecordType::IXFR | RecordType::AXFR | RecordType::MAILB | RecordType::MAILA | RecordType::ANY => {
           let data = buff.read_bytes(len as usize)?;
           Self::Unknown {
             domain,
             ttl,
             len,
-            record_type: v,
+            record_type: record_type.into(),
             class,
             data: data.to_vec(),
           }
         },
 
         RecordType::OPT => {
-          buff.seek(buff.pos() - 10);
-
-          let udp_payload_size = buff.read_u16()?;
-          let extended_rcode = buff.read_u8()?;
-          let version = buff.read_u8()?;
-
-          let reserved = buff.read_u16()?;
-          let dnssec_ok: bool = (reserved & 0x8000) >> 15 == 1;
+          // CLASS holds the UDP payload size and TTL the extended RCODE, version and flags (RFC6891 section 6.1.3)
+          let udp_payload_size = u16::from(class);
+          let [extended_rcode, version, flags, _] = ttl.to_be_bytes();
+          let dnssec_ok = flags & 0b1000_0000 != 0;
 
-          let data_length = buff.read_u16()?;
-
-          let pos = buff.pos();
           let mut options = vec![];
 
-          loop {
-            let opt = EDNSOption::parse(buff)?;
-            options.push(opt);
-
-            if buff.pos() - pos >= (data_length as usize) {
-              break;
-            }
+          while buff.pos() < end {
+            options.push(EDNSOption::parse(buff)?);
           }
 
           Self::OPT { udp_payload_size, extended_rcode, version, dnssec_ok, options }
@@ -200,7 +549,34 @@ impl Record {
 
         RecordType::CNAME => {
           let (_, host) = buff.read_labels(true)?;
-          Self::NS { domain, host, ttl, class }
+          Self::CNAME { domain, host, ttl, class }
+        },
+
+        RecordType::SOA => {
+          let (_, mname) = buff.read_labels(true)?;
+          let (_, rname) = buff.read_labels(true)?;
+          let serial = buff.read_u32()?;
+          let refresh = buff.read_u32()?;
+          let retry = buff.read_u32()?;
+          let expire = buff.read_u32()?;
+          let minimum = buff.read_u32()?;
+          Self::SOA { domain, mname, rname, serial, refresh, retry, expire, minimum, ttl, class }
+        },
+
+        RecordType::NULL => {
+          let data = buff.read_bytes(len as usize)?.to_vec();
+          Self::NULL { domain, data, ttl, class }
+        },
+
+        RecordType::PTR => {
+          let (_, host) = buff.read_labels(true)?;
+          Self::PTR { domain, host, ttl, class }
+        },
+
+        RecordType::HINFO => {
+          let cpu = buff.read_character_string()?;
+          let os = buff.read_character_string()?;
+          Self::HINFO { domain, cpu, os, ttl, class }
         },
 
         RecordType::MX => {
@@ -209,20 +585,61 @@ impl Record {
           Self::MX { domain, priority, host, ttl, class }
         },
 
+        RecordType::TXT => {
+          let mut strings = vec![];
+
+          while buff.pos() < end {
+            strings.push(buff.read_character_string()?);
+          }
+
+          Self::TXT { domain, strings, ttl, class }
+        },
+
         RecordType::AAAA => {
           let addr = Ipv6Addr::from_bits(buff.read_u128()?);
           Self::AAAA { domain, class, ttl, addr }
         },
 
+        RecordType::SRV => {
+          let priority = buff.read_u16()?;
+          let weight = buff.read_u16()?;
+          let port = buff.read_u16()?;
+          let (_, target) = buff.read_labels(true)?;
+          Self::SRV { domain, priority, weight, port, target, ttl, class }
+        },
+
+        RecordType::NAPTR => {
+          let order = buff.read_u16()?;
+          let preference = buff.read_u16()?;
+          let flags = buff.read_character_string()?;
+          let services = buff.read_character_string()?;
+          let regexp = buff.read_character_string()?;
+          let (_, replacement) = buff.read_labels(true)?;
+          Self::NAPTR { domain, order, preference, flags, services, regexp, replacement, ttl, class }
+        },
+
+        RecordType::DNAME => {
+          let (_, target) = buff.read_labels(true)?;
+          Self::DNAME { domain, target, ttl, class }
+        },
+
         RecordType::DS => {
           let key_tag = buff.read_u16()?;
           let algorithm = buff.read_u8()?.into();
           let digest_type = buff.read_u8()?.into();
-          let digest = buff.read_bytes(len as usize - 4)?.to_vec();
+          let digest = buff.read_bytes(remaining(buff, end))?.to_vec();
 
           Self::DS { domain, class, ttl, key_tag, algorithm, digest_type, digest }
         },
 
+        RecordType::SSHFP => {
+          let algorithm = buff.read_u8()?.into();
+          let fingerprint_type = buff.read_u8()?.into();
+          let fingerprint = buff.read_bytes(remaining(buff, end))?.to_vec();
+
+          Self::SSHFP { domain, class, ttl, algorithm, fingerprint_type, fingerprint }
+        },
+
         RecordType::RRSIG => {
           let type_covered = buff.read_u16()?;
           let algorithm = buff.read_u8()?.into();
@@ -232,10 +649,8 @@ impl Record {
           let signature_inception = buff.read_u32()?;
           let key_tag = buff.read_u16()?;
 
-          let (signer_name_len, signer_name) = buff.read_labels(false)?;
-
-          let signature_length = len as usize - (18 + signer_name_len);
-          let signature = buff.read_bytes(signature_length)?.to_vec();
+          let (_, signer_name) = buff.read_labels(false)?;
+          let signature = buff.read_bytes(remaining(buff, end))?.to_vec();
 
           Self::RRSIG {
             domain,
@@ -254,9 +669,9 @@ impl Record {
         },
 
         RecordType::NSEC => {
-          let (ndn_len, next_domain_name) = buff.read_labels(false)?;
+          let (_, next_domain_name) = buff.read_labels(false)?;
 
-          let type_bitmaps = buff.read_bytes(len as usize - ndn_len)?;
+          let type_bitmaps = buff.read_bytes(remaining(buff, end))?;
           let record_types = RecordType::parse_type_bitmaps(type_bitmaps.into())?;
 
           Self::NSEC { domain, class, ttl, next_domain_name, record_types }
@@ -266,32 +681,24 @@ impl Record {
           let flags = buff.read_u16()?;
           let protocol = buff.read_u8()?;
           let algorithm = buff.read_u8()?.into();
+          let public_key = buff.read_bytes(remaining(buff, end))?.to_vec();
 
-          let is_zone_key = (flags >> 7) & 0b1 == 1;
-          let is_secure_entry_point = (flags >> 15) & 0b1 == 1;
-
-          let public_key = buff.read_bytes(len as usize - 4)?.to_vec();
-
-          Self::DNSKEY { domain, class, ttl, is_secure_entry_point, is_zone_key, public_key, protocol, algorithm }
+          Self::DNSKEY { domain, class, ttl, flags, public_key, protocol, algorithm }
         },
 
         RecordType::NSEC3 => {
           let hash_algorithm = buff.read_u8()?;
-
           let flags = buff.read_u8()?;
-          let opt_out = (flags >> 7) == 1;
-
           let iterations = buff.read_u16()?;
           let salt_length = buff.read_u8()?;
           let salt = buff.read_bytes(salt_length as usize)?.to_vec();
           let hash_length = buff.read_u8()?;
           let next_hashed_owner_name = buff.read_bytes(hash_length as usize)?.to_vec();
 
-          let type_bitmaps_length = len as usize - 6 - (salt_length + hash_length) as usize;
-          let type_bitmaps = buff.read_bytes(type_bitmaps_length)?;
+          let type_bitmaps = buff.read_bytes(remaining(buff, end))?;
           let record_types = RecordType::parse_type_bitmaps(type_bitmaps.into())?;
 
-          Self::NSEC3 { domain, class, ttl, hash_algorithm, opt_out, iterations, salt_length, salt, hash_length, next_hashed_owner_name, record_types }
+          Self::NSEC3 { domain, class, ttl, hash_algorithm, flags, iterations, salt_length, salt, hash_length, next_hashed_owner_name, record_types }
         },
 
         RecordType::NSEC3PARAM => {
@@ -301,171 +708,216 @@ impl Record {
           let salt_length = buff.read_u8()?;
           let salt = buff.read_bytes(salt_length as usize)?.to_vec();
 
-          if flags != 0 {
-            return Ok(None); // if flags is not set to 0 then this record should be ignored
+          Self::NSEC3PARAM { domain, class, ttl, hash_algorithm, flags, iterations, salt_length, salt }
+        },
+
+        RecordType::TLSA | RecordType::SMIMEA => {
+          let cert_usage = buff.read_u8()?.into();
+          let selector = buff.read_u8()?.into();
+          let matching_type = buff.read_u8()?.into();
+          let data = buff.read_bytes(remaining(buff, end))?.to_vec();
+
+          match record_type {
+            RecordType::TLSA => Self::TLSA { domain, class, ttl, cert_usage, selector, matching_type, data },
+            _ => Self::SMIMEA { domain, class, ttl, cert_usage, selector, matching_type, data },
           }
+        },
 
-          Self::NSEC3PARAM { domain, class, ttl, hash_algorithm, flags, iterations, salt_length, salt }
+        RecordType::OPENPGPKEY => {
+          let public_key = buff.read_bytes(len as usize)?.to_vec();
+          Self::OPENPGPKEY { domain, class, ttl, public_key }
+        },
+
+        RecordType::SVCB | RecordType::HTTPS => {
+          let priority = buff.read_u16()?;
+          let (_, target) = buff.read_labels(false)?;
+
+          let mut params = vec![];
+
+          while buff.pos() < end {
+            params.push(SvcParam::parse(buff)?);
+          }
+
+          SvcParam::validate(&params)?;
+
+          match record_type {
+            RecordType::SVCB => Self::SVCB { domain, priority, target, params, ttl, class },
+            _ => Self::HTTPS { domain, priority, target, params, ttl, class },
+          }
         },
-      }))
+
+        RecordType::URI => {
+          let priority = buff.read_u16()?;
+          let weight = buff.read_u16()?;
+          let target = buff.read_bytes(remaining(buff, end))?.to_vec();
+          Self::URI { domain, priority, weight, target, ttl, class }
+        },
+
+        RecordType::CAA => {
+          let flags = buff.read_u8()?;
+
+          let tag = buff.read_character_string()?;
+          let value = buff.read_bytes(remaining(buff, end))?.to_vec();
+          let property = CAAValue::parse(&tag, &value)?;
+
+          Self::CAA { domain, class, ttl, flags, tag, value, property }
+        },
+      };
+
+      // typed parsers must consume exactly RDLENGTH octets
+      if buff.pos() != end {
+        return Err(DrasilDNSError::InvalidRDataLength { record_type: record_type.into(), size: len as usize });
+      }
+
+      Ok(Some(record))
     })
   }
 
   pub(crate) fn write_bytes(&self, buff: &mut Buffer) -> Result<(), DrasilDNSError> {
-    let mut b = Buffer::with_capacity(0);
-    b.set_expandable(true);
+    buff.write_transaction(|b| self.write_rr(b))
+  }
+
+  fn write_rr(&self, b: &mut Buffer) -> Result<(), DrasilDNSError> {
+    let record_type = self.record_type();
+
+    b.write_labels_compressed(self.domain())?;
+    b.write_u16(record_type.into())?;
+    b.write_u16(self.class().into())?;
+    b.write_u32(self.ttl())?;
+
+    let pos = b.pos();
+    b.write_u16(0)?;
+
+    self.write_rdata(b)?;
+
+    let len = b.pos() - (pos + 2);
+    if len > u16::MAX as usize {
+      return Err(DrasilDNSError::InvalidRDataLength { record_type: record_type.into(), size: len });
+    }
+    b.patch_bytes(pos, &(len as u16).to_be_bytes())?;
+
+    Ok(())
+  }
 
+  fn write_rdata(&self, b: &mut Buffer) -> Result<(), DrasilDNSError> {
     match self {
-      Record::Unknown {
-        domain,
-        ttl,
-        len,
-        record_type,
-        class,
-        data,
-      } => {
-        b.write_labels(domain)?;
-        b.write_u16(*record_type)?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-        b.write_u32(*len)?;
-        b.write_bytes(&data)?;
+      Record::Unknown { len, data, record_type, .. } => {
+        if *len as usize != data.len() {
+          return Err(DrasilDNSError::InvalidRDataLength { record_type: *record_type, size: data.len() });
+        }
+        b.write_bytes(data)?; // opaque, never contains compressed names (RFC3597)
       },
 
-      Record::OPT {
-        udp_payload_size,
-        extended_rcode,
-        version,
-        dnssec_ok,
-        options,
-      } => {
-        b.write_u8(0)?; // set domain to empty (0)
-        b.write_u16(RecordType::OPT.into())?;
-        b.write_u16(*udp_payload_size)?;
-        b.write_u8(*extended_rcode)?;
-        b.write_u8(*version)?;
-        b.write_u16(if *dnssec_ok { 0x8000 } else { 0x0000 })?;
-        
-        let pos = b.pos();
-        b.write_u16(0)?;
-
+      Record::OPT { options, .. } => {
         for opt in options {
-          opt.write_bytes(&mut b)?;
+          opt.write_bytes(b)?;
         }
+      },
 
-        let len = (b.pos() - (pos + 2)) as u16;
-        b.set_bytes(pos, &len.to_be_bytes())?;
+      Record::A { addr, .. } => b.write_u32(addr.to_bits())?,
+
+      Record::NS { host, .. } | Record::CNAME { host, .. } | Record::PTR { host, .. } => {
+        b.write_labels_compressed(host)?;
       },
 
-      Record::A {
-        domain,
-        addr,
-        ttl,
-        class,
+      Record::SOA {
+        mname,
+        rname,
+        serial,
+        refresh,
+        retry,
+        expire,
+        minimum,
+        ..
       } => {
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::A.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-        b.write_u32(4)?;
-        b.write_u32(addr.to_bits())?;
-      },
-
-      Record::NS {
-        domain,
-        host,
-        ttl,
-        class,
-      } => {
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::NS.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
+        b.write_labels_compressed(mname)?;
+        b.write_labels_compressed(rname)?;
+        b.write_u32(*serial)?;
+        b.write_u32(*refresh)?;
+        b.write_u32(*retry)?;
+        b.write_u32(*expire)?;
+        b.write_u32(*minimum)?;
+      },
 
-        let pos = b.pos();
-        b.write_u32(0)?;
+      Record::NULL { data, .. } => b.write_bytes(data)?,
 
-        let len = b.write_labels(host)? as u32;
-        b.set_bytes(pos, &len.to_be_bytes())?;
+      Record::HINFO { cpu, os, .. } => {
+        b.write_character_string(cpu)?;
+        b.write_character_string(os)?;
       },
 
-      Record::CNAME {
-        domain,
-        host,
-        ttl,
-        class,
-      } => {
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::CNAME.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-
-        let pos = b.pos();
-        b.write_u32(0)?;
+      Record::TXT { strings, .. } => {
+        for string in strings {
+          b.write_character_string(string)?;
+        }
+      },
 
-        let len = b.write_labels(host)? as u32;
-        b.set_bytes(pos, &len.to_be_bytes())?;
+      Record::MX { priority, host, .. } => {
+        b.write_u16(*priority)?;
+        b.write_labels_compressed(host)?;
       },
 
-      Record::MX {
-        domain,
+      Record::AAAA { addr, .. } => b.write_u128(addr.to_bits())?,
+
+      Record::SRV {
         priority,
-        host,
-        ttl,
-        class,
+        weight,
+        port,
+        target,
+        ..
       } => {
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::MX.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-        
-        let pos = b.pos();
-        b.write_u32(0)?;
-
         b.write_u16(*priority)?;
-        let len = b.write_labels(host)? as u32;
-
-        b.set_bytes(pos, &(len + 2).to_be_bytes())?;
+        b.write_u16(*weight)?;
+        b.write_u16(*port)?;
+        b.write_labels(target)?; // must not be compressed (RFC2782)
       },
 
-      Record::AAAA {
-        domain,
-        addr,
-        ttl,
-        class,
+      Record::NAPTR {
+        order,
+        preference,
+        flags,
+        services,
+        regexp,
+        replacement,
+        ..
       } => {
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::A.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-        b.write_u32(16)?;
-        b.write_u128(addr.to_bits())?;
+        b.write_u16(*order)?;
+        b.write_u16(*preference)?;
+        b.write_character_string(flags)?;
+        b.write_character_string(services)?;
+        b.write_character_string(regexp)?;
+        b.write_labels(replacement)?; // must not be compressed (RFC3403)
+      },
+
+      Record::DNAME { target, .. } => {
+        b.write_labels(target)?; // must not be compressed (RFC6672)
       },
 
       Record::DS {
-        domain,
-        class,
-        ttl,
         key_tag,
         algorithm,
         digest_type,
         digest,
+        ..
       } => {
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::DS.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-        b.write_u32(4 + digest.len() as u32)?;
         b.write_u16(*key_tag)?;
         b.write_u8((*algorithm).into())?;
         b.write_u8((*digest_type).into())?;
         b.write_bytes(digest)?;
       },
 
+      Record::SSHFP {
+        algorithm,
+        fingerprint_type,
+        fingerprint,
+        ..
+      } => {
+        b.write_u8((*algorithm).into())?;
+        b.write_u8((*fingerprint_type).into())?;
+        b.write_bytes(fingerprint)?;
+      },
+
       Record::RRSIG {
-        domain,
-        class,
-        ttl,
         type_covered,
         algorithm,
         labels,
@@ -475,15 +927,8 @@ impl Record {
         key_tag,
         signer_name,
         signature,
+        ..
       } => {
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::RRSIG.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-
-        let pos = b.pos();
-        b.write_u32(0)?;
-
         b.write_u16(*type_covered)?;
         b.write_u8((*algorithm).into())?;
         b.write_u8(*labels)?;
@@ -491,140 +936,163 @@ impl Record {
         b.write_u32(*signature_expiration)?;
         b.write_u32(*signature_inception)?;
         b.write_u16(*key_tag)?;
-        b.write_labels(signer_name)?;
-        b.write_bytes(&signature)?;
-
-        let len = b.pos() - (pos + 4);
-        b.set_bytes(pos, &len.to_be_bytes())?;
+        b.write_labels(signer_name)?; // must not be compressed (RFC4034)
+        b.write_bytes(signature)?;
       },
 
       Record::NSEC {
-        domain,
-        class,
-        ttl,
         next_domain_name,
         record_types,
+        ..
       } => {
-        let type_bitmaps = RecordType::into_type_bitmaps(record_types)?;
-
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::NSEC.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-
-        let pos = b.pos();
-        b.write_u32(0)?;
-
-        b.write_labels(next_domain_name)?;
-        b.write_buffer(&type_bitmaps)?;
-
-        b.set_bytes(pos, &(pos + 4).to_be_bytes())?;
+        b.write_labels(next_domain_name)?; // must not be compressed (RFC4034)
+        b.write_buffer(&RecordType::into_type_bitmaps(record_types)?)?;
       },
 
       Record::DNSKEY {
-        domain,
-        class,
-        ttl,
-        is_secure_entry_point,
-        is_zone_key,
+        flags,
         protocol,
         algorithm,
         public_key,
+        ..
       } => {
-        let mut flags = 0b0;
-        if *is_zone_key {
-          flags |= 0b1 << 7;
-        }
-        if *is_secure_entry_point {
-          flags |= 0b1 << 15;
-        }
-
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::DNSKEY.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-        b.write_u32(4 + public_key.len() as u32)?;
-        b.write_u16(flags)?;
+        b.write_u16(*flags)?;
         b.write_u8(*protocol)?;
         b.write_u8((*algorithm).into())?;
         b.write_bytes(public_key)?;
       },
 
       Self::NSEC3 {
-        domain,
-        class,
-        ttl,
         hash_algorithm,
-        opt_out,
+        flags,
         iterations,
         salt_length,
         salt,
         hash_length,
         next_hashed_owner_name,
         record_types,
+        ..
       } => {
-        let type_bitmaps = RecordType::into_type_bitmaps(record_types)?;
-
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::NSEC3.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
-
-        let pos = b.pos();
-        b.write_u32(0)?;
-
         b.write_u8(*hash_algorithm)?;
-        b.write_u8(if *opt_out { 0b1000_0000 } else { 0 })?;
+        b.write_u8(*flags)?;
         b.write_u16(*iterations)?;
         b.write_u8(*salt_length)?;
         b.write_bytes(salt)?;
         b.write_u8(*hash_length)?;
-        b.write_bytes(&next_hashed_owner_name)?;
-        b.write_buffer(&type_bitmaps)?;
-
-        b.set_bytes(pos, &(pos + 4).to_be_bytes())?;
+        b.write_bytes(next_hashed_owner_name)?;
+        b.write_buffer(&RecordType::into_type_bitmaps(record_types)?)?;
       },
 
       Self::NSEC3PARAM {
-        domain,
-        class,
-        ttl,
         hash_algorithm,
         flags,
         iterations,
         salt_length,
         salt,
+        ..
       } => {
-        if *flags == 0 {
-          return Ok(()); // flag should be equal to 0 else ignore this record
-        }
-
-        b.write_labels(domain)?;
-        b.write_u16(RecordType::NSEC3PARAM.into())?;
-        b.write_u16((*class).into())?;
-        b.write_u32(*ttl)?;
         b.write_u8(*hash_algorithm)?;
         b.write_u8(*flags)?;
         b.write_u16(*iterations)?;
         b.write_u8(*salt_length)?;
         b.write_bytes(salt)?;
       },
+
+      Record::TLSA {
+        cert_usage,
+        selector,
+        matching_type,
+        data,
+        ..
+      }
+      | Record::SMIMEA {
+        cert_usage,
+        selector,
+        matching_type,
+        data,
+        ..
+      } => {
+        b.write_u8((*cert_usage).into())?;
+        b.write_u8((*selector).into())?;
+        b.write_u8((*matching_type).into())?;
+        b.write_bytes(data)?;
+      },
+
+      Record::OPENPGPKEY { public_key, .. } => b.write_bytes(public_key)?,
+
+      Record::SVCB {
+        priority,
+        target,
+        params,
+        ..
+      }
+      | Record::HTTPS {
+        priority,
+        target,
+        params,
+        ..
+      } => {
+        SvcParam::validate(params)?;
+
+        b.write_u16(*priority)?;
+        b.write_labels(target)?; // must not be compressed (RFC9460)
+
+        for param in params {
+          param.write_bytes(b)?;
+        }
+      },
+
+      Record::URI {
+        priority,
+        weight,
+        target,
+        ..
+      } => {
+        b.write_u16(*priority)?;
+        b.write_u16(*weight)?;
+        b.write_bytes(target)?;
+      },
+
+      Record::CAA {
+        flags,
+        tag,
+        value,
+        ..
+      } => {
+        b.write_u8(*flags)?;
+        b.write_character_string(tag)?;
+        b.write_bytes(value)?;
+      },
     }
 
-    buff.write_buffer(&b)?;
     Ok(())
   }
 }
 
+/// Returns the number of RDATA octets left before `end`, zero if the parser already read past it
+fn remaining(buff: &Buffer, end: usize) -> usize {
+  end.saturating_sub(buff.pos())
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  /// Builds a record in wire format from its owner name (already in wire format), type and RDATA, with class IN and TTL 60
+  fn wire(owner: &[u8], record_type: u16, rdata: &[u8]) -> Vec<u8> {
+    let mut data = owner.to_vec();
+    data.extend(record_type.to_be_bytes());
+    data.extend([0, 1, 0, 0, 0, 60]);
+    data.extend((rdata.len() as u16).to_be_bytes());
+    data.extend(rdata);
+    data
+  }
+
   #[test]
   fn record_rw() {
     let records: Vec<Record> = vec![
       Record::A {
-        domain: vec!["google".to_string(), "com".to_string()],
+        domain: "google.com".parse().unwrap(),
         addr: Ipv4Addr::from_bits(0x10101010),
         ttl: 60,
         class: RecordClass::IN,
@@ -641,6 +1109,164 @@ mod tests {
           EDNSOption::ClientSubnet { family: 2, source_netmask: 75, scope_netmask: 0, addr: 0x10101010 },
         ],
       },
+
+      Record::SOA {
+        domain: "google.com".parse().unwrap(),
+        mname: "ns1.google.com".parse().unwrap(),
+        rname: "dns-admin.google.com".parse().unwrap(),
+        serial: 2024010101,
+        refresh: 900,
+        retry: 900,
+        expire: 1800,
+        minimum: 60,
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::NULL {
+        domain: "google.com".parse().unwrap(),
+        data: vec![0, 1, 2, 3],
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::PTR {
+        domain: "1.0.0.127.in-addr.arpa".parse().unwrap(),
+        host: "localhost".parse().unwrap(),
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::HINFO {
+        domain: "google.com".parse().unwrap(),
+        cpu: b"x86_64".to_vec(),
+        os: b"Linux".to_vec(),
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::TXT {
+        domain: "google.com".parse().unwrap(),
+        strings: vec![b"v=spf1 -all".to_vec(), vec![], vec![0xFF; 255]],
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::SRV {
+        domain: "_sip._tcp.google.com".parse().unwrap(),
+        priority: 10,
+        weight: 60,
+        port: 5060,
+        target: "sip.google.com".parse().unwrap(),
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::NAPTR {
+        domain: "google.com".parse().unwrap(),
+        order: 100,
+        preference: 10,
+        flags: b"U".to_vec(),
+        services: b"E2U+sip".to_vec(),
+        regexp: b"!^\\+1(.*)$!sip:\\1@google.com!".to_vec(),
+        replacement: Name::root(),
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::SSHFP {
+        domain: "host.google.com".parse().unwrap(),
+        class: RecordClass::IN,
+        ttl: 60,
+        algorithm: SSHFPAlgorithm::ED25519,
+        fingerprint_type: SSHFPFingerprintType::SHA256,
+        fingerprint: vec![0xAB; 32],
+      },
+
+      Record::TLSA {
+        domain: "_443._tcp.google.com".parse().unwrap(),
+        class: RecordClass::IN,
+        ttl: 60,
+        cert_usage: TLSACertUsage::DANEEE,
+        selector: TLSASelector::SPKI,
+        matching_type: TLSAMatchingType::SHA256,
+        data: vec![0xCD; 32],
+      },
+
+      Record::SMIMEA {
+        domain: "c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6._smimecert.google.com".parse().unwrap(),
+        class: RecordClass::IN,
+        ttl: 60,
+        cert_usage: TLSACertUsage::PKIXEE,
+        selector: TLSASelector::CERT,
+        matching_type: TLSAMatchingType::Unknown(7),
+        data: vec![0x30, 0x82, 0x01, 0x0A],
+      },
+
+      Record::OPENPGPKEY {
+        domain: "c93f1e400f26708f98cb19d936620da35eec8f72e57f9eec01c1afd6._openpgpkey.google.com".parse().unwrap(),
+        class: RecordClass::IN,
+        ttl: 60,
+        public_key: vec![0x99, 0x01, 0x0D],
+      },
+
+      Record::SVCB {
+        domain: "_8443._foo.api.google.com".parse().unwrap(),
+        priority: 0,
+        target: "svc4.google.net".parse().unwrap(),
+        params: vec![],
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::HTTPS {
+        domain: "google.com".parse().unwrap(),
+        priority: 1,
+        target: Name::root(),
+        params: vec![
+          SvcParam::Mandatory { keys: vec![svcb::SvcParamKey::ALPN] },
+          SvcParam::ALPN { ids: vec![b"h2".to_vec(), b"h3".to_vec()] },
+          SvcParam::IPv4Hint { addrs: vec![Ipv4Addr::new(192, 0, 2, 1)] },
+        ],
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::URI {
+        domain: "_ftp._tcp.google.com".parse().unwrap(),
+        priority: 10,
+        weight: 1,
+        target: b"ftp://ftp.google.com/public".to_vec(),
+        ttl: 60,
+        class: RecordClass::IN,
+      },
+
+      Record::CAA {
+        domain: "google.com".parse().unwrap(),
+        class: RecordClass::IN,
+        ttl: 60,
+        flags: 0,
+        tag: b"issue".to_vec(),
+        value: b"pki.goog; validationmethods=dns-01".to_vec(),
+        property: CAAValue::parse(b"issue", b"pki.goog; validationmethods=dns-01").unwrap(),
+      },
+
+      Record::CAA {
+        domain: "google.com".parse().unwrap(),
+        class: RecordClass::IN,
+        ttl: 60,
+        flags: 0b1000_0001,
+        tag: b"tbs".to_vec(),
+        value: b"Unknown".to_vec(),
+        property: CAAValue::Unknown { tag: b"tbs".to_vec(), value: b"Unknown".to_vec() },
+      },
+
+      Record::DNAME {
+        domain: "google.org".parse().unwrap(),
+        target: "google.com".parse().unwrap(),
+        ttl: 60,
+        class: RecordClass::IN,
+      },
     ];
 
     let mut b = Buffer::with_capacity(0);
@@ -662,5 +1288,105 @@ mod tests {
     }
 
     assert_eq!(records, records_after_read, "Records not equals after write+read");
+
+    // records parsed from the wire must be written back byte for byte and parse into the same record
+    let google = b"\x06google\x03com\x00";
+    let wires = [
+      wire(google, 6, &[
+        b"\x03ns1\x06google\x03com\x00\x09dns-admin\x06google\x03com\x00".as_slice(),
+        &[0x78, 0xA3, 0x2B, 0x35, 0, 0, 0x03, 0x84, 0, 0, 0x03, 0x84, 0, 0, 0x07, 0x08, 0, 0, 0, 0x3C],
+      ].concat()),
+      wire(google, 10, &[0x00, 0x01, 0xFF]),
+      wire(b"\x011\x010\x010\x03127\x07in-addr\x04arpa\x00", 12, b"\x09localhost\x00"),
+      wire(google, 13, b"\x06x86_64\x05Linux"),
+      wire(google, 16, b"\x0bv=spf1 -all\x00\x05hello"),
+      wire(b"\x04_sip\x04_tcp\x06google\x03com\x00", 33, b"\x00\x0a\x00\x3c\x13\xc4\x03sip\x06google\x03com\x00"),
+      wire(google, 35, b"\x00\x64\x00\x0a\x01u\x07E2U+sip\x1e!^\\+1(.*)$!sip:\\1@example.com!\x00"),
+      wire(google, 35, b"\x00\x64\x00\x32\x01s\x07SIP+D2U\x00\x04_sip\x04_udp\x06Google\x03com\x00"),
+      wire(b"\x04_ftp\x04_tcp\x06google\x03com\x00", 256, b"\x00\x0a\x00\x01ftp://ftp.google.com/public"),
+      wire(b"\x04host\x06google\x03com\x00", 44, &[&[4, 2][..], &[0xAB; 32]].concat()),
+      wire(b"\x04_443\x04_tcp\x06google\x03com\x00", 52, &[&[3, 1, 1][..], &[0xCD; 32]].concat()),
+      wire(b"\x04c93f\x0a_smimecert\x06google\x03com\x00", 53, &[1, 0, 7, 0x30, 0x82, 0x01, 0x0A]),
+      wire(b"\x04c93f\x0b_openpgpkey\x06google\x03com\x00", 61, &[0x99, 0x01, 0x0D]),
+      // unassigned NSEC3 flags are kept next to Opt-Out
+      wire(b"\x04abcd\x06google\x03com\x00", 50, &[&b"\x01\x03\x00\x0c\x04\xaa\xbb\xcc\xdd\x14"[..], &[0x5A; 20], b"\x00\x06\x40\x00\x00\x00\x00\x02"].concat()),
+      wire(b"\x06google\x03com\x00", 257, b"\x81\x03tbsUnknown"),
+      // known tags keep their case and the whitespace of their value
+      wire(b"\x06google\x03com\x00", 257, b"\x00\x05ISSUEca.example;  policy=ev"),
+    ];
+
+    for data in wires {
+      let record = Record::parse(&mut data[..].into()).expect("Failed at record read").expect("Record skipped unnecessarily");
+
+      let mut b = Buffer::with_capacity(0);
+      b.set_expandable(true);
+      record.write_bytes(&mut b).expect("Failed at record write");
+      let written: Vec<u8> = b.into();
+      assert_eq!(written, data, "{} not equal after read+write", record.record_type());
+
+      let reparsed = Record::parse(&mut written[..].into()).expect("Failed at record read").expect("Record skipped unnecessarily");
+      assert_eq!(reparsed, record, "{} not equal after read+write+read", record.record_type());
+    }
+  }
+
+  #[test]
+  fn record_rdlength() {
+    // example.com. A 10.0.0.1 with RDLENGTH 4
+    let mut data = vec![7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 10, 0, 0, 1];
+    let record = Record::parse(&mut data[..].into()).expect("Failed at record read").unwrap();
+    assert_eq!(record.rdata().unwrap(), vec![10, 0, 0, 1]);
+
+    // RDLENGTH larger than the A record data
+    data[22] = 5;
+    data.push(0);
+    assert!(matches!(Record::parse(&mut data[..].into()), Err(DrasilDNSError::InvalidRDataLength { record_type: 1, size: 5 })), "Under-read RDATA accepted");
+
+    // RDLENGTH smaller than the A record data
+    data[22] = 3;
+    assert!(matches!(Record::parse(&mut data[..].into()), Err(DrasilDNSError::InvalidRDataLength { record_type: 1, size: 3 })), "Over-read RDATA accepted");
+
+    // unknown type keeps a compression pointer in its RDATA as opaque data (RFC3597 section 4)
+    let data = [3, b'c', b'o', b'm', 0, 0xff, 0x00, 0, 1, 0, 0, 0, 60, 0, 2, 0xc0, 0x00];
+    let record = Record::parse(&mut data[..].into()).expect("Failed at unknown record read").unwrap();
+    let Record::Unknown { record_type, len, data: rdata, .. } = &record else {
+      panic!("Unknown type not parsed as Record::Unknown");
+    };
+    assert_eq!((*record_type, *len), (0xff00, 2));
+    assert_eq!(rdata, &vec![0xc0, 0x00]);
+    assert_eq!(generic::GenericRData(record.rdata().unwrap()).to_string(), "\\# 2 c000");
+
+    let mismatched = Record::Unknown { domain: Name::root(), ttl: 0, len: 3, record_type: 0xff00, class: RecordClass::IN, data: vec![0] };
+    let mut b = Buffer::with_capacity(64);
+    assert!(mismatched.write_bytes(&mut b).is_err(), "Unknown record with wrong len written");
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn dname_cname_synthesis() {
+    let dname = Record::DNAME {
+      domain: "example.com".parse().unwrap(),
+      target: "example.net".parse().unwrap(),
+      ttl: 300,
+      class: RecordClass::IN,
+    };
+
+    let cname = dname.synthesize_cname(&"www.A.example.com".parse().unwrap()).expect("Failed to synthesize CNAME");
+    assert_eq!(cname, Some(Record::CNAME {
+      domain: "www.a.example.com".parse().unwrap(),
+      host: "www.A.example.net".parse().unwrap(),
+      ttl: 300,
+      class: RecordClass::IN,
+    }), "Synthesized CNAME incorrect");
+
+    assert_eq!(dname.synthesize_cname(&"example.com".parse().unwrap()).unwrap(), None, "CNAME synthesized for DNAME owner");
+    assert_eq!(dname.synthesize_cname(&"www.example.org".parse().unwrap()).unwrap(), None, "CNAME synthesized for unrelated name");
+
+    let long_target = Record::DNAME {
+      domain: "example.com".parse().unwrap(),
+      target: vec!["a".repeat(63); 3].join(".").parse().unwrap(),
+      ttl: 300,
+      class: RecordClass::IN,
+    };
+    let long_qname: Name = format!("{}.example.com", "b".repeat(63)).parse().unwrap();
+    assert!(matches!(long_target.synthesize_cname(&long_qname), Err(DrasilDNSError::NameTooLarge { .. })), "Overlong substitution accepted");
+  }
+}
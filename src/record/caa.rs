@@ -0,0 +1,181 @@
+// ===== Imports =====
+use crate::{error::DrasilDNSError, name::Name};
+// ===================
+
+/// # CAA Issuer
+/// Value of the `issue`, `issuewild` and `issuemail` properties (RFC8659 section 4.2).
+/// An empty issuer means that no certificate authority is authorized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CAAIssuer {
+  pub issuer: Option<Name>,
+  pub params: Vec<(String, String)>,
+}
+
+impl CAAIssuer {
+  /// Parses an issue-value, returns `None` if it doesn't follow the grammar of RFC8659
+  fn parse(value: &[u8]) -> Option<Self> {
+    let value = std::str::from_utf8(value).ok()?;
+    let (issuer, params) = match value.split_once(';') {
+      Some((issuer, params)) => (issuer, Some(params)),
+      None => (value, None),
+    };
+
+    let issuer = issuer.trim_matches(is_wsp);
+    let issuer = if issuer.is_empty() {
+      None
+    } else {
+      if !issuer.split('.').all(is_ldh) {
+        return None;
+      }
+      Some(issuer.parse().ok()?)
+    };
+
+    let mut parsed = vec![];
+    if let Some(params) = params.map(|p| p.trim_matches(is_wsp)).filter(|p| !p.is_empty()) {
+      for param in params.split(';') {
+        let (tag, value) = param.split_once('=')?;
+        let tag = tag.trim_matches(is_wsp);
+        let value = value.trim_matches(is_wsp);
+
+        if !is_ldh(tag) || !value.bytes().all(|b| (0x21..=0x7E).contains(&b) && b != b';') {
+          return None;
+        }
+        parsed.push((tag.to_string(), value.to_string()));
+      }
+    }
+
+    Some(Self { issuer, params: parsed })
+  }
+
+  fn to_bytes(&self) -> Vec<u8> {
+    let mut value = match &self.issuer {
+      Some(issuer) => issuer.labels()
+        .map(|label| String::from_utf8_lossy(label).to_string())
+        .collect::<Vec<_>>()
+        .join("."),
+      None => ";".to_string(),
+    };
+
+    for (i, (tag, val)) in self.params.iter().enumerate() {
+      if i > 0 || self.issuer.is_some() {
+        value.push_str("; ");
+      }
+      value.push_str(&format!("{}={}", tag, val));
+    }
+
+    value.into_bytes()
+  }
+}
+
+fn is_wsp(c: char) -> bool {
+  c == ' ' || c == '\t'
+}
+
+/// Tells whether the string is a label (or tag) made of letters, digits and inner hyphens
+fn is_ldh(s: &str) -> bool {
+  !s.is_empty()
+    && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    && !s.starts_with('-')
+    && !s.ends_with('-')
+}
+
+/// # CAA Value
+/// Enum for representing the property (tag and value pair) of a CAA record.
+/// Values of known tags are parsed into structured forms, any other tag (or a known tag whose value
+/// doesn't follow its grammar) is kept as raw bytes so that it can be written back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CAAValue {
+  Unknown {
+    tag: Vec<u8>,
+    value: Vec<u8>,
+  },
+
+  /// Authorizes the issuer to issue certificates for the domain
+  Issue(CAAIssuer),
+
+  /// Authorizes the issuer to issue wildcard certificates for the domain
+  IssueWild(CAAIssuer),
+
+  /// Authorizes the issuer to issue S/MIME certificates for the domain (RFC9495)
+  IssueMail(CAAIssuer),
+
+  /// URL to which certificate authorities may report policy violations
+  Iodef {
+    url: String,
+  },
+}
+
+impl CAAValue {
+  /// Builds the property from raw tag and value, tags are matched case-insensitively
+  pub fn parse(tag: &[u8], value: &[u8]) -> Result<Self, DrasilDNSError> {
+    if tag.is_empty() || !tag.iter().all(|b| b.is_ascii_alphanumeric()) {
+      return Err(DrasilDNSError::InvalidData { msg: "CAA tag must be a non-empty alphanumeric string".to_string() });
+    }
+
+    let known = match tag.to_ascii_lowercase().as_slice() {
+      b"issue" => CAAIssuer::parse(value).map(Self::Issue),
+      b"issuewild" => CAAIssuer::parse(value).map(Self::IssueWild),
+      b"issuemail" => CAAIssuer::parse(value).map(Self::IssueMail),
+      b"iodef" => String::from_utf8(value.to_vec()).ok().map(|url| Self::Iodef { url }),
+      _ => None,
+    };
+
+    Ok(known.unwrap_or_else(|| Self::Unknown { tag: tag.to_vec(), value: value.to_vec() }))
+  }
+
+  /// Returns the tag of the property
+  pub fn tag(&self) -> &[u8] {
+    match self {
+      Self::Unknown { tag, .. } => tag,
+      Self::Issue(_) => b"issue",
+      Self::IssueWild(_) => b"issuewild",
+      Self::IssueMail(_) => b"issuemail",
+      Self::Iodef { .. } => b"iodef",
+    }
+  }
+
+  /// Returns the value of the property in wire format
+  pub fn value(&self) -> Vec<u8> {
+    match self {
+      Self::Unknown { value, .. } => value.clone(),
+      Self::Issue(issuer) | Self::IssueWild(issuer) | Self::IssueMail(issuer) => issuer.to_bytes(),
+      Self::Iodef { url } => url.as_bytes().to_vec(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn caa_value_parse() {
+    let value = CAAValue::parse(b"issue", b" ca.example.net ;  account=230123; policy=ev").expect("Failed to parse CAA value");
+    let issuer = CAAIssuer {
+      issuer: Some("ca.example.net".parse().unwrap()),
+      params: vec![("account".to_string(), "230123".to_string()), ("policy".to_string(), "ev".to_string())],
+    };
+    assert_eq!(value, CAAValue::Issue(issuer), "Issue value not parsed correctly");
+    assert_eq!(value.value(), b"ca.example.net; account=230123; policy=ev", "Issue value not written correctly");
+
+    let value = CAAValue::parse(b"IssueWild", b";").expect("Failed to parse CAA value");
+    assert_eq!(value, CAAValue::IssueWild(CAAIssuer { issuer: None, params: vec![] }), "Empty issuer not parsed correctly");
+    assert_eq!(value.value(), b";", "Empty issuer not written correctly");
+
+    let value = CAAValue::parse(b"iodef", b"mailto:security@example.com").expect("Failed to parse CAA value");
+    assert_eq!(value, CAAValue::Iodef { url: "mailto:security@example.com".to_string() }, "Iodef value not parsed correctly");
+
+    for (tag, raw) in [(&b"tbs"[..], &b"Unknown \xFF value"[..]), (b"issue", b"-bad-.example; x")] {
+      let value = CAAValue::parse(tag, raw).expect("Failed to parse CAA value");
+      assert_eq!(value.tag(), tag, "Raw tag not preserved");
+      assert_eq!(value.value(), raw, "Raw value not preserved");
+    }
+
+    assert!(CAAValue::parse(b"", b"").is_err(), "Empty tag accepted");
+    assert!(CAAValue::parse(b"is sue", b"").is_err(), "Non-alphanumeric tag accepted");
+  }
+}
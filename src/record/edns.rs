@@ -5,6 +5,8 @@ use crate::{buffer::Buffer, error::DrasilDNSError};
 
 /// Enum for EDNS(0) option types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum EDNSOptionType {
   Unknown(u16),
@@ -19,9 +21,9 @@ pub enum EDNSOptionType {
   ECS = 16,
 }
 
-impl Into<u16> for EDNSOptionType {
-  fn into(self) -> u16 {
-    match self {
+impl From<EDNSOptionType> for u16 {
+  fn from(value: EDNSOptionType) -> Self {
+    match value {
       EDNSOptionType::Unknown(v) => v,
       EDNSOptionType::NSID => 3,
       EDNSOptionType::ClientSubnet => 8,
@@ -57,6 +59,8 @@ impl From<u16> for EDNSOptionType {
 /// Enum for representing all EDNS(0) options.
 /// Options are the {attribute, value} pairs stored in the RDATA section of OPT RR.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EDNSOption {
   Unknown {
     code: u16,
@@ -125,8 +129,8 @@ impl EDNSOption {
 
       Ok(match code {
         3 => {
-          let data = buff.read_bytes(len as usize - 2)?;
-          Self::NSID { data: String::from_utf8_lossy(&data).to_string() }
+          let data = buff.read_bytes(len as usize)?;
+          Self::NSID { data: String::from_utf8_lossy(data).to_string() }
         },
 
         8 => {
@@ -162,7 +166,7 @@ impl EDNSOption {
           } else if source_netmask <= 64 {
             addr = buff.read_u64()? as u128;
           } else if source_netmask <= 128 {
-            addr = buff.read_u128()? as u128;
+            addr = buff.read_u128()?;
           }
 
           Self::ClientSubnet { family, source_netmask, scope_netmask, addr }
@@ -213,7 +217,7 @@ impl EDNSOption {
           }
 
           let mut tags = vec![];
-          for _ in 0..(len % 2) {
+          for _ in 0..(len / 2) {
             tags.push(buff.read_u16()?);
           }
 
@@ -221,6 +225,10 @@ impl EDNSOption {
         },
 
         15 => {
+          if len < 2 {
+            return Err(DrasilDNSError::InvalidEDNSOptionLength { option_type: 15, size: len });
+          }
+
           let info_code = buff.read_u16()?;
           let extra_text = buff.read_bytes(len as usize - 2)?;
 
@@ -278,7 +286,7 @@ impl EDNSOption {
         let mut b = Buffer::with_capacity(4 + *len as usize);
         b.write_u16(*code)?;
         b.write_u16(*len)?;
-        b.write_bytes(&data)?;
+        b.write_bytes(data)?;
         buff.write_buffer(&b)?;
       },
 
@@ -414,10 +422,10 @@ impl EDNSOption {
       },
 
       EDNSOption::EcsIPv4 { source_netmask, scope_netmask, addr } => {
-        let mut b = Buffer::with_capacity(14);
+        let mut b = Buffer::with_capacity(12);
 
         b.write_u16(EDNSOptionType::ECS.into())?;
-        b.write_u16(10)?;
+        b.write_u16(8)?;
         b.write_u16(1)?;
         b.write_u8(*source_netmask)?;
         b.write_u8(*scope_netmask)?;
@@ -0,0 +1,83 @@
+
+// ===== Imports =====
+use std::{fmt, str::FromStr};
+use crate::error::DrasilDNSError;
+// ===================
+
+/// # Generic RDATA
+/// RDATA in the generic presentation format of RFC3597 section 5: `\# <length> <hex data>`.
+/// Used for record types without a typed representation, but any RDATA may be written this way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericRData(pub Vec<u8>);
+
+impl fmt::Display for GenericRData {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "\\# {}", self.0.len())?;
+
+    if !self.0.is_empty() {
+      f.write_str(" ")?;
+      for byte in &self.0 {
+        write!(f, "{:02x}", byte)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl FromStr for GenericRData {
+  type Err = DrasilDNSError;
+
+  /// The hex data may be split into several whitespace separated words, the length is checked against it.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut words = s.split_whitespace();
+
+    if words.next() != Some("\\#") {
+      return Err(DrasilDNSError::InvalidData { msg: "generic RDATA must start with \\#".to_string() });
+    }
+
+    let len: u16 = words.next()
+      .and_then(|len| len.parse().ok())
+      .ok_or_else(|| DrasilDNSError::InvalidData { msg: "missing or invalid generic RDATA length".to_string() })?;
+
+    let hex: String = words.collect();
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+      return Err(DrasilDNSError::InvalidData { msg: format!("invalid hex in generic RDATA ({})", hex) });
+    }
+
+    let data: Vec<u8> = (0..hex.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&hex[i..(i + 2)], 16).unwrap_or_default()) // digits were checked above
+      .collect();
+
+    if data.len() != len as usize {
+      return Err(DrasilDNSError::InvalidData { msg: format!("generic RDATA length {} does not match {} octets of data", len, data.len()) });
+    }
+
+    Ok(Self(data))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn generic_rdata_presentation() {
+    // examples from RFC3597 section 5
+    let rdata: GenericRData = "\\# 4 0A000001".parse().expect("Failed at parsing generic RDATA");
+    assert_eq!(rdata.0, vec![10, 0, 0, 1]);
+    assert_eq!(rdata.to_string(), "\\# 4 0a000001");
+
+    let rdata: GenericRData = "\\# 0".parse().expect("Failed at parsing empty generic RDATA");
+    assert!(rdata.0.is_empty());
+    assert_eq!(rdata.to_string(), "\\# 0");
+
+    let rdata: GenericRData = "\\# 5 0102 03 0405".parse().expect("Failed at parsing split generic RDATA");
+    assert_eq!(rdata.0, vec![1, 2, 3, 4, 5]);
+
+    assert!("\\# 3 0102".parse::<GenericRData>().is_err(), "Length mismatch wasn't rejected");
+    assert!("\\# 1 0g".parse::<GenericRData>().is_err(), "Invalid hex wasn't rejected");
+    assert!("# 1 00".parse::<GenericRData>().is_err(), "Missing \\# wasn't rejected");
+  }
+}
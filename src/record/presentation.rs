@@ -0,0 +1,534 @@
+
+// ===== Imports =====
+use std::{collections::HashSet, fmt, str::FromStr};
+use crate::{
+  buffer::Buffer,
+  error::DrasilDNSError,
+  name::Name,
+  presentation::{self, Token, Tokens},
+  record::{caa::CAAValue, generic::GenericRData, svcb::SvcParam, Record},
+  types::{dnssec::DNSSECAlgorithm, RecordClass, RecordType},
+};
+// ===================
+
+fn invalid(msg: String) -> DrasilDNSError {
+  DrasilDNSError::InvalidData { msg }
+}
+
+/// Writes the type list of NSEC and NSEC3 records in increasing order
+fn fmt_types(types: &HashSet<RecordType>) -> String {
+  let mut types: Vec<RecordType> = types.iter().copied().collect();
+  types.sort_by_key(|&t| u16::from(t));
+  types.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+fn parse_types(tokens: &mut Tokens) -> Result<HashSet<RecordType>, DrasilDNSError> {
+  tokens.rest().iter().map(|t| t.text.parse()).collect()
+}
+
+/// Salt and hash of NSEC3 records are written as `-` when empty (RFC5155 section 3.3)
+fn parse_optional<F>(token: &Token, parse: F) -> Result<Vec<u8>, DrasilDNSError>
+where
+  F: Fn(&str) -> Result<Vec<u8>, DrasilDNSError>,
+{
+  match token.text.as_str() {
+    "-" => Ok(vec![]),
+    text => parse(text),
+  }
+}
+
+fn fmt_optional(data: &[u8], fmt: fn(&[u8]) -> String) -> String {
+  match data.is_empty() {
+    true => "-".to_string(),
+    false => fmt(data),
+  }
+}
+
+fn length_u8(data: &[u8], field: &str) -> Result<u8, DrasilDNSError> {
+  u8::try_from(data.len()).map_err(|_| invalid(format!("{} is longer than 255 octets", field)))
+}
+
+impl Record {
+  /// Returns the RDATA of the record in presentation format (RFC1035 section 5.1).
+  /// Types without a presentation format of their own (`NULL`, `OPT` and unknown types) use the generic form of RFC3597.
+  pub(crate) fn rdata_to_string(&self) -> Result<String, DrasilDNSError> {
+    let cs = presentation::fmt_character_string;
+
+    let parts: Vec<String> = match self {
+      Record::Unknown { data, .. } => vec![GenericRData(data.clone()).to_string()],
+      Record::OPT { .. } | Record::NULL { .. } => vec![GenericRData(self.rdata()?).to_string()],
+
+      Record::A { addr, .. } => vec![addr.to_string()],
+      Record::AAAA { addr, .. } => vec![addr.to_string()],
+
+      Record::NS { host, .. } | Record::CNAME { host, .. } | Record::PTR { host, .. } => vec![host.to_string()],
+      Record::DNAME { target, .. } => vec![target.to_string()],
+
+      Record::SOA { mname, rname, serial, refresh, retry, expire, minimum, .. } => vec![
+        mname.to_string(),
+        rname.to_string(),
+        serial.to_string(),
+        refresh.to_string(),
+        retry.to_string(),
+        expire.to_string(),
+        minimum.to_string(),
+      ],
+
+      Record::HINFO { cpu, os, .. } => vec![cs(cpu), cs(os)],
+      Record::MX { priority, host, .. } => vec![priority.to_string(), host.to_string()],
+      Record::TXT { strings, .. } => strings.iter().map(|s| cs(s)).collect(),
+
+      Record::SRV { priority, weight, port, target, .. } => {
+        vec![priority.to_string(), weight.to_string(), port.to_string(), target.to_string()]
+      },
+
+      Record::NAPTR { order, preference, flags, services, regexp, replacement, .. } => vec![
+        order.to_string(),
+        preference.to_string(),
+        cs(flags),
+        cs(services),
+        cs(regexp),
+        replacement.to_string(),
+      ],
+
+      Record::DS { key_tag, algorithm, digest_type, digest, .. } => vec![
+        key_tag.to_string(),
+        u8::from(*algorithm).to_string(),
+        u8::from(*digest_type).to_string(),
+        presentation::fmt_hex(digest),
+      ],
+
+      Record::SSHFP { algorithm, fingerprint_type, fingerprint, .. } => vec![
+        u8::from(*algorithm).to_string(),
+        u8::from(*fingerprint_type).to_string(),
+        presentation::fmt_hex(fingerprint),
+      ],
+
+      Record::RRSIG {
+        type_covered,
+        algorithm,
+        labels,
+        original_ttl,
+        signature_expiration,
+        signature_inception,
+        key_tag,
+        signer_name,
+        signature,
+        ..
+      } => vec![
+        RecordType::from(*type_covered).to_string(),
+        u8::from(*algorithm).to_string(),
+        labels.to_string(),
+        original_ttl.to_string(),
+        presentation::fmt_timestamp(*signature_expiration),
+        presentation::fmt_timestamp(*signature_inception),
+        key_tag.to_string(),
+        signer_name.to_string(),
+        presentation::fmt_base64(signature),
+      ],
+
+      Record::NSEC { next_domain_name, record_types, .. } => vec![next_domain_name.to_string(), fmt_types(record_types)],
+
+      Record::DNSKEY { flags, protocol, algorithm, public_key, .. } => vec![
+        flags.to_string(),
+        protocol.to_string(),
+        u8::from(*algorithm).to_string(),
+        presentation::fmt_base64(public_key),
+      ],
+
+      Record::NSEC3 { hash_algorithm, flags, iterations, salt, next_hashed_owner_name, record_types, .. } => vec![
+        hash_algorithm.to_string(),
+        flags.to_string(),
+        iterations.to_string(),
+        fmt_optional(salt, presentation::fmt_hex),
+        fmt_optional(next_hashed_owner_name, presentation::fmt_base32hex),
+        fmt_types(record_types),
+      ],
+
+      Record::NSEC3PARAM { hash_algorithm, flags, iterations, salt, .. } => vec![
+        hash_algorithm.to_string(),
+        flags.to_string(),
+        iterations.to_string(),
+        fmt_optional(salt, presentation::fmt_hex),
+      ],
+
+      Record::TLSA { cert_usage, selector, matching_type, data, .. }
+      | Record::SMIMEA { cert_usage, selector, matching_type, data, .. } => vec![
+        u8::from(*cert_usage).to_string(),
+        u8::from(*selector).to_string(),
+        u8::from(*matching_type).to_string(),
+        presentation::fmt_hex(data),
+      ],
+
+      Record::OPENPGPKEY { public_key, .. } => vec![presentation::fmt_base64(public_key)],
+
+      Record::SVCB { priority, target, params, .. } | Record::HTTPS { priority, target, params, .. } => {
+        let mut parts = vec![priority.to_string(), target.to_string()];
+        parts.extend(params.iter().map(|p| p.to_string()));
+        parts
+      },
+
+      Record::URI { priority, weight, target, .. } => vec![priority.to_string(), weight.to_string(), cs(target)],
+
+      Record::CAA { flags, tag, value, .. } => vec![
+        flags.to_string(),
+        String::from_utf8_lossy(tag).to_string(), // tags are alphanumeric
+        cs(value),
+      ],
+    };
+
+    Ok(parts.into_iter().filter(|p| !p.is_empty()).collect::<Vec<_>>().join(" "))
+  }
+
+  /// Builds a record from the presentation format of its RDATA, consuming all remaining tokens.
+  /// Relative names are completed with `origin`, without one every name is treated as fully qualified.
+  pub(crate) fn from_rdata_tokens(
+    domain: Name,
+    ttl: u32,
+    class: RecordClass,
+    record_type: RecordType,
+    t: &mut Tokens,
+    origin: Option<&Name>,
+  ) -> Result<Self, DrasilDNSError> {
+    // any type may be written in the generic form (RFC3597 section 5)
+    if t.peek().is_some_and(|t| !t.quoted && t.text == "\\#") {
+      let text = t.rest().iter().map(|t| t.text.as_str()).collect::<Vec<_>>().join(" ");
+      let GenericRData(data) = text.parse()?;
+      return Self::from_generic_rdata(domain, ttl, class, record_type, data);
+    }
+
+    let cs = presentation::parse_character_string;
+
+    let record = match record_type {
+      RecordType::Unknown(_) | RecordType::OPT | RecordType::NULL
+      | RecordType::IXFR | RecordType::AXFR | RecordType::MAILB | RecordType::MAILA | RecordType::ANY => {
+        return Err(invalid(format!("RDATA of {} records must use the generic \\# form", record_type)));
+      },
+
+      RecordType::A => {
+        let token = t.next("address")?;
+        let addr = token.text.parse().map_err(|_| invalid(format!("invalid IPv4 address \"{}\"", token.text)))?;
+        Self::A { domain, addr, ttl, class }
+      },
+
+      RecordType::AAAA => {
+        let token = t.next("address")?;
+        let addr = token.text.parse().map_err(|_| invalid(format!("invalid IPv6 address \"{}\"", token.text)))?;
+        Self::AAAA { domain, addr, ttl, class }
+      },
+
+      RecordType::NS => Self::NS { domain, host: t.name("host", origin)?, ttl, class },
+      RecordType::CNAME => Self::CNAME { domain, host: t.name("host", origin)?, ttl, class },
+      RecordType::PTR => Self::PTR { domain, host: t.name("host", origin)?, ttl, class },
+      RecordType::DNAME => Self::DNAME { domain, target: t.name("target", origin)?, ttl, class },
+
+      RecordType::SOA => Self::SOA {
+        domain,
+        mname: t.name("primary name server", origin)?,
+        rname: t.name("responsible mailbox", origin)?,
+        serial: t.number("serial")?,
+        refresh: t.ttl("refresh")?,
+        retry: t.ttl("retry")?,
+        expire: t.ttl("expire")?,
+        minimum: t.ttl("minimum")?,
+        ttl,
+        class,
+      },
+
+      RecordType::HINFO => Self::HINFO { domain, cpu: cs(t.next("CPU")?)?, os: cs(t.next("OS")?)?, ttl, class },
+
+      RecordType::MX => Self::MX { domain, priority: t.number("priority")?, host: t.name("host", origin)?, ttl, class },
+
+      RecordType::TXT => {
+        let strings = t.rest().iter().map(cs).collect::<Result<_, _>>()?;
+        Self::TXT { domain, strings, ttl, class }
+      },
+
+      RecordType::SRV => Self::SRV {
+        domain,
+        priority: t.number("priority")?,
+        weight: t.number("weight")?,
+        port: t.number("port")?,
+        target: t.name("target", origin)?,
+        ttl,
+        class,
+      },
+
+      RecordType::NAPTR => Self::NAPTR {
+        domain,
+        order: t.number("order")?,
+        preference: t.number("preference")?,
+        flags: cs(t.next("flags")?)?,
+        services: cs(t.next("services")?)?,
+        regexp: cs(t.next("regexp")?)?,
+        replacement: t.name("replacement", origin)?,
+        ttl,
+        class,
+      },
+
+      RecordType::DS => Self::DS {
+        domain,
+        class,
+        ttl,
+        key_tag: t.number("key tag")?,
+        algorithm: t.next("algorithm")?.text.parse::<DNSSECAlgorithm>()?,
+        digest_type: t.number::<u8>("digest type")?.into(),
+        digest: presentation::parse_hex(&t.concat())?,
+      },
+
+      RecordType::SSHFP => Self::SSHFP {
+        domain,
+        class,
+        ttl,
+        algorithm: t.number::<u8>("algorithm")?.into(),
+        fingerprint_type: t.number::<u8>("fingerprint type")?.into(),
+        fingerprint: presentation::parse_hex(&t.concat())?,
+      },
+
+      RecordType::RRSIG => Self::RRSIG {
+        domain,
+        class,
+        ttl,
+        type_covered: t.next("type covered")?.text.parse::<RecordType>()?.into(),
+        algorithm: t.next("algorithm")?.text.parse::<DNSSECAlgorithm>()?,
+        labels: t.number("labels")?,
+        original_ttl: t.number("original TTL")?,
+        signature_expiration: presentation::parse_timestamp(&t.next("signature expiration")?.text)?,
+        signature_inception: presentation::parse_timestamp(&t.next("signature inception")?.text)?,
+        key_tag: t.number("key tag")?,
+        signer_name: t.name("signer name", origin)?,
+        signature: presentation::parse_base64(&t.concat())?,
+      },
+
+      RecordType::NSEC => Self::NSEC {
+        domain,
+        class,
+        ttl,
+        next_domain_name: t.name("next domain name", origin)?,
+        record_types: parse_types(t)?,
+      },
+
+      RecordType::DNSKEY => Self::DNSKEY {
+        domain,
+        class,
+        ttl,
+        flags: t.number("flags")?,
+        protocol: t.number("protocol")?,
+        algorithm: t.next("algorithm")?.text.parse::<DNSSECAlgorithm>()?,
+        public_key: presentation::parse_base64(&t.concat())?,
+      },
+
+      RecordType::NSEC3 => {
+        let hash_algorithm = t.number("hash algorithm")?;
+        let flags: u8 = t.number("flags")?;
+        let iterations = t.number("iterations")?;
+        let salt = parse_optional(t.next("salt")?, presentation::parse_hex)?;
+        let next_hashed_owner_name = parse_optional(t.next("next hashed owner name")?, presentation::parse_base32hex)?;
+
+        Self::NSEC3 {
+          domain,
+          class,
+          ttl,
+          hash_algorithm,
+          flags,
+          iterations,
+          salt_length: length_u8(&salt, "salt")?,
+          salt,
+          hash_length: length_u8(&next_hashed_owner_name, "next hashed owner name")?,
+          next_hashed_owner_name,
+          record_types: parse_types(t)?,
+        }
+      },
+
+      RecordType::NSEC3PARAM => {
+        let hash_algorithm = t.number("hash algorithm")?;
+        let flags = t.number("flags")?;
+        let iterations = t.number("iterations")?;
+        let salt = parse_optional(t.next("salt")?, presentation::parse_hex)?;
+
+        Self::NSEC3PARAM { domain, class, ttl, hash_algorithm, flags, iterations, salt_length: length_u8(&salt, "salt")?, salt }
+      },
+
+      RecordType::TLSA | RecordType::SMIMEA => {
+        let cert_usage = t.number::<u8>("certificate usage")?.into();
+        let selector = t.number::<u8>("selector")?.into();
+        let matching_type = t.number::<u8>("matching type")?.into();
+        let data = presentation::parse_hex(&t.concat())?;
+
+        match record_type {
+          RecordType::TLSA => Self::TLSA { domain, class, ttl, cert_usage, selector, matching_type, data },
+          _ => Self::SMIMEA { domain, class, ttl, cert_usage, selector, matching_type, data },
+        }
+      },
+
+      RecordType::OPENPGPKEY => Self::OPENPGPKEY { domain, class, ttl, public_key: presentation::parse_base64(&t.concat())? },
+
+      RecordType::SVCB | RecordType::HTTPS => {
+        let priority = t.number("priority")?;
+        let target = t.name("target", origin)?;
+
+        let mut params = vec![];
+        while let Some(token) = t.peek() {
+          t.next("parameter")?;
+          let mut text = token.text.clone();
+
+          // a quoted value is a token of its own, e.g. `alpn="h2,h3"`
+          if text.ends_with('=') && t.peek().is_some_and(|v| v.quoted) {
+            text.push_str(&t.next("parameter value")?.text);
+          }
+          params.push(SvcParam::from_str(&text)?);
+        }
+
+        SvcParam::validate(&params)?;
+
+        match record_type {
+          RecordType::SVCB => Self::SVCB { domain, priority, target, params, ttl, class },
+          _ => Self::HTTPS { domain, priority, target, params, ttl, class },
+        }
+      },
+
+      RecordType::URI => Self::URI {
+        domain,
+        priority: t.number("priority")?,
+        weight: t.number("weight")?,
+        target: presentation::unescape(&t.next("target")?.text)?,
+        ttl,
+        class,
+      },
+
+      RecordType::CAA => {
+        let flags: u8 = t.number("flags")?;
+        let tag = presentation::unescape(&t.next("tag")?.text)?;
+        let value = presentation::unescape(&t.next("value")?.text)?;
+
+        let property = CAAValue::parse(&tag, &value)?;
+
+        Self::CAA { domain, class, ttl, flags, tag, value, property }
+      },
+    };
+
+    t.finish()?;
+    Ok(record)
+  }
+
+  /// Builds a record from the presentation format of its RDATA given as a single string, every name is treated as fully qualified
+  #[cfg(any(test, feature = "serde"))]
+  pub(crate) fn from_rdata_str(
+    domain: Name,
+    ttl: u32,
+    class: RecordClass,
+    record_type: RecordType,
+    rdata: &str,
+  ) -> Result<Self, DrasilDNSError> {
+    let tokens: Vec<Token> = presentation::entries(rdata)?.into_iter().flat_map(|e| e.tokens).collect();
+    Self::from_rdata_tokens(domain, ttl, class, record_type, &mut Tokens::new(&tokens), None)
+  }
+
+  /// Builds a record of any type from its RDATA in wire format, names inside it must not be compressed
+  pub(crate) fn from_generic_rdata(domain: Name, ttl: u32, class: RecordClass, record_type: RecordType, data: Vec<u8>) -> Result<Self, DrasilDNSError> {
+    let len = u16::try_from(data.len())
+      .map_err(|_| DrasilDNSError::InvalidRDataLength { record_type: record_type.into(), size: data.len() })?;
+
+    if let RecordType::Unknown(v) = record_type {
+      return Ok(Self::Unknown { domain, ttl, len, record_type: v, class, data });
+    }
+
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
+    b.write_labels(&domain)?;
+    b.write_u16(record_type.into())?;
+    b.write_u16(class.into())?;
+    b.write_u32(ttl)?;
+    b.write_u16(len)?;
+    b.write_bytes(&data)?;
+
+    let wire: Vec<u8> = b.into();
+    Self::parse(&mut wire.into())?.ok_or(DrasilDNSError::Unknown)
+  }
+}
+
+impl fmt::Display for Record {
+  /// Writes the record in master-file format (RFC1035 section 5.1): `<owner> <ttl> <class> <type> <rdata>`
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {} {} {}", self.domain(), self.ttl(), self.class(), self.record_type())?;
+
+    let rdata = self.rdata_to_string().map_err(|_| fmt::Error)?;
+    if !rdata.is_empty() {
+      write!(f, " {}", rdata)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl FromStr for Record {
+  type Err = DrasilDNSError;
+
+  /// Parses a record in master-file format. The owner name and TTL are required, the class defaults to `IN`
+  /// and may come before or after the TTL. Every name is treated as fully qualified.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let entries = presentation::entries(s)?;
+    let [entry] = entries.as_slice() else {
+      return Err(invalid(format!("expected a single record, found {}", entries.len())));
+    };
+
+    let mut t = Tokens::new(&entry.tokens);
+    let domain = t.name("owner name", None)?;
+
+    let (ttl, class) = t.ttl_and_class();
+    let ttl = ttl.ok_or_else(|| invalid("missing TTL".to_string()))?;
+    let record_type: RecordType = t.next("record type")?.text.parse()?;
+
+    Self::from_rdata_tokens(domain, ttl, class.unwrap_or(RecordClass::IN), record_type, &mut t, None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn record_presentation() {
+    let records = [
+      ("example.com. 300 IN A 192.0.2.1", None),
+      ("example.com. 300 IN MX 10 mail.example.com.", None),
+      ("example.com. 300 IN TXT \"v=spf1 -all\" \"a\\\"b\\\\c\\000\"", None),
+      ("example.com. 300 IN SOA ns1.example.com. hostmaster.example.com. 2024010101 7200 3600 1209600 300", None),
+      ("example.com. 300 IN DS 60485 5 1 2BB183AF5F22588179A53B0A98631FAD1A292118", None),
+      ("example.com. 300 IN NSEC host.example.com. A MX RRSIG NSEC TYPE1234", None),
+      ("example.com. 300 IN NSEC3 1 1 12 AABBCCDD 2t7b4g4vsa5smi47k61mv5bv1a22bojr A RRSIG", None),
+      ("example.com. 300 IN NSEC3 1 3 0 - 2t7b4g4vsa5smi47k61mv5bv1a22bojr", None),
+      ("example.com. 300 IN CAA 0 issue \"ca.example.net; account=230123\"", None),
+      ("example.com. 300 IN CAA 129 tbs \"Unknown\"", None),
+      ("_443._tcp.example.com. 300 IN TLSA 3 1 1 0C72AC70B745AC19998811B131D662C9AC69DBDBE7CB23E5B514B56664C5D3D6", None),
+      ("example.com. 300 IN HTTPS 1 . alpn=h2,h3 port=8443 ipv4hint=192.0.2.1,192.0.2.2 key667=hello", None),
+      ("example.com. 300 IN TYPE65280 \\# 4 0a000001", None),
+      ("example.com. 300 IN NULL \\# 0", None),
+      // other accepted forms, along with the form they are written back in
+      ("example.com. IN 60 a 192.0.2.1", Some("example.com. 60 IN A 192.0.2.1")),
+      ("example.com. 60 TYPE1 \\# 4 C0000201", Some("example.com. 60 IN A 192.0.2.1")),
+      ("example.com. 60 CLASS1 HTTPS 1 . alpn=\"h2,h3\" ech=AAEC", Some("example.com. 60 IN HTTPS 1 . alpn=h2,h3 ech=AAEC")),
+      (
+        "example.com. 60 IN RRSIG A RSASHA1 2 3600 ( 20030322173103 1048354263\n 2642 example.com. oJB1W6WNGv+ldvQ3WDG0MQkg5IEhjRip8WTr PYGv07h108dUKGMeDPKijVCHX3DDKdfb+v6o B9wfuh3DTJXUAfI= )",
+        Some("example.com. 60 IN RRSIG A 5 2 3600 20030322173103 20030322173103 2642 example.com. oJB1W6WNGv+ldvQ3WDG0MQkg5IEhjRip8WTrPYGv07h108dUKGMeDPKijVCHX3DDKdfb+v6oB9wfuh3DTJXUAfI="),
+      ),
+    ];
+
+    for (text, displayed) in records {
+      let record: Record = text.parse().unwrap_or_else(|e| panic!("Failed to parse \"{}\": {}", text, e));
+      assert_eq!(record.to_string(), displayed.unwrap_or(text), "Record not displayed correctly");
+    }
+
+    for text in [
+      "example.com. IN A 192.0.2.1",
+      "example.com. 300 IN A 192.0.2.1 192.0.2.2",
+      "example.com. 300 IN A 192.0.2",
+      "example.com. 300 IN TYPE65280 00",
+      "example.com. 300 IN A \\# 3 000000",
+      "example.com. 300 IN HTTPS 1 . port=80 alpn=h2",
+      "example.com. 300 IN A 192.0.2.1\nexample.com. 300 IN A 192.0.2.2",
+    ] {
+      assert!(text.parse::<Record>().is_err(), "Invalid record \"{}\" accepted", text);
+    }
+  }
+}
@@ -0,0 +1,106 @@
+
+// ===== Imports =====
+use crate::{
+  error::DrasilDNSError,
+  name::Name,
+  record::Record,
+  types::{RecordClass, RecordType},
+};
+// ===================
+
+/// # RRset
+/// Set of records sharing owner name, class and type (RFC2181 section 5), the unit DNSSEC signs.
+/// Always holds at least one record. OPT records are not part of any RRset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRset {
+  records: Vec<Record>,
+}
+
+impl RRset {
+  /// Creates an RRset from the provided records.
+  /// Fails with `DrasilDNSError::InvalidData` if there are none, one of them is an OPT record
+  /// or they differ in owner name, class or type.
+  pub fn new(records: Vec<Record>) -> Result<Self, DrasilDNSError> {
+    let Some(first) = records.first() else {
+      return Err(DrasilDNSError::InvalidData { msg: "an RRset needs at least one record".to_string() });
+    };
+
+    if let Some(r) = records.iter().find(|r| matches!(r, Record::OPT { .. }) || !Self::belongs(first, r)) {
+      return Err(DrasilDNSError::InvalidData {
+        msg: format!("record {} {} {} doesn't belong in RRset {} {} {}", r.domain(), r.class(), r.record_type(), first.domain(), first.class(), first.record_type()),
+      });
+    }
+
+    Ok(Self { records })
+  }
+
+  /// Groups records into RRsets (same owner name, class and type), in order of their first appearance.
+  /// OPT records are left out.
+  pub fn group(records: impl IntoIterator<Item = Record>) -> Vec<RRset> {
+    let mut rrsets: Vec<RRset> = vec![];
+
+    for r in records {
+      if let Record::OPT { .. } = r {
+        continue;
+      }
+
+      match rrsets.iter_mut().find(|set| Self::belongs(&set.records[0], &r)) {
+        Some(set) => set.records.push(r),
+        None => rrsets.push(RRset { records: vec![r] }),
+      }
+    }
+
+    rrsets
+  }
+
+  fn belongs(first: &Record, r: &Record) -> bool {
+    first.record_type() == r.record_type() && first.class() == r.class() && first.domain() == r.domain()
+  }
+
+  /// Returns the owner name of the records
+  pub fn name(&self) -> &Name {
+    self.records[0].domain()
+  }
+
+  /// Returns the class of the records
+  pub fn class(&self) -> RecordClass {
+    self.records[0].class()
+  }
+
+  /// Returns the type of the records
+  pub fn record_type(&self) -> RecordType {
+    self.records[0].record_type()
+  }
+
+  /// Returns the TTL of the RRset, the lowest TTL of its records (RFC2181 section 5.2)
+  pub fn ttl(&self) -> u32 {
+    self.records.iter().map(|r| r.ttl()).min().unwrap_or(0)
+  }
+
+  /// Returns the records of the RRset
+  pub fn records(&self) -> &[Record] {
+    &self.records
+  }
+
+  /// Returns the records of the RRset, consuming it
+  pub fn into_records(self) -> Vec<Record> {
+    self.records
+  }
+
+  /// Sorts the records into canonical order, by their canonical RDATA compared as left-justified unsigned octet
+  /// sequences, and drops duplicates which an RRset must not contain (RFC4034 section 6.3).
+  /// The RRset is left unchanged if the RDATA of one of the records can't be written.
+  pub fn sort_canonical(&mut self) -> Result<(), DrasilDNSError> {
+    let mut keyed = self.records.iter()
+      .enumerate()
+      .map(|(i, r)| Ok((r.to_canonical_rdata()?, i)))
+      .collect::<Result<Vec<_>, DrasilDNSError>>()?;
+
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    keyed.dedup_by(|a, b| a.0 == b.0);
+
+    let mut records: Vec<Option<Record>> = std::mem::take(&mut self.records).into_iter().map(Some).collect();
+    self.records = keyed.into_iter().filter_map(|(_, i)| records[i].take()).collect();
+    Ok(())
+  }
+}
@@ -0,0 +1,471 @@
+// ===== Imports =====
+use std::{fmt, net::{Ipv4Addr, Ipv6Addr}, str::FromStr};
+use crate::{buffer::Buffer, error::DrasilDNSError, presentation};
+// ===================
+
+/// Enum for SVCB/HTTPS parameter keys (RFC9460 section 14.3.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u16)]
+pub enum SvcParamKey {
+  Mandatory = 0,
+  ALPN = 1,
+  NoDefaultALPN = 2,
+  Port = 3,
+  IPv4Hint = 4,
+  ECH = 5,
+  IPv6Hint = 6,
+  Unknown(u16),
+  InvalidKey = 65535,
+}
+
+impl From<SvcParamKey> for u16 {
+  fn from(value: SvcParamKey) -> Self {
+    match value {
+      SvcParamKey::Unknown(v) => v,
+      SvcParamKey::Mandatory => 0,
+      SvcParamKey::ALPN => 1,
+      SvcParamKey::NoDefaultALPN => 2,
+      SvcParamKey::Port => 3,
+      SvcParamKey::IPv4Hint => 4,
+      SvcParamKey::ECH => 5,
+      SvcParamKey::IPv6Hint => 6,
+      SvcParamKey::InvalidKey => 65535,
+    }
+  }
+}
+
+impl From<u16> for SvcParamKey {
+  fn from(value: u16) -> Self {
+    match value {
+      0 => Self::Mandatory,
+      1 => Self::ALPN,
+      2 => Self::NoDefaultALPN,
+      3 => Self::Port,
+      4 => Self::IPv4Hint,
+      5 => Self::ECH,
+      6 => Self::IPv6Hint,
+      65535 => Self::InvalidKey,
+      v => Self::Unknown(v),
+    }
+  }
+}
+
+impl fmt::Display for SvcParamKey {
+  /// Writes the name of the key, or the `key<n>` form for keys without one (RFC9460 section 2.1)
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Mandatory => f.write_str("mandatory"),
+      Self::ALPN => f.write_str("alpn"),
+      Self::NoDefaultALPN => f.write_str("no-default-alpn"),
+      Self::Port => f.write_str("port"),
+      Self::IPv4Hint => f.write_str("ipv4hint"),
+      Self::ECH => f.write_str("ech"),
+      Self::IPv6Hint => f.write_str("ipv6hint"),
+      Self::Unknown(_) | Self::InvalidKey => write!(f, "key{}", u16::from(*self)),
+    }
+  }
+}
+
+impl FromStr for SvcParamKey {
+  type Err = DrasilDNSError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "mandatory" => Ok(Self::Mandatory),
+      "alpn" => Ok(Self::ALPN),
+      "no-default-alpn" => Ok(Self::NoDefaultALPN),
+      "port" => Ok(Self::Port),
+      "ipv4hint" => Ok(Self::IPv4Hint),
+      "ech" => Ok(Self::ECH),
+      "ipv6hint" => Ok(Self::IPv6Hint),
+      _ => s.strip_prefix("key")
+        .filter(|v| !v.starts_with('+'))
+        .and_then(|v| v.parse::<u16>().ok())
+        .map(Self::from)
+        .ok_or_else(|| DrasilDNSError::InvalidSvcParams { msg: format!("unknown key \"{}\"", s) }),
+    }
+  }
+}
+
+/// # Service Parameter
+/// Enum for representing SvcParams, the {key, value} pairs stored in the RDATA of SVCB and HTTPS RRs (RFC9460).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SvcParam {
+  Unknown {
+    key: u16,
+    data: Vec<u8>,
+  },
+
+  /// Keys which must be understood by the client for the record to be usable
+  Mandatory {
+    keys: Vec<SvcParamKey>,
+  }, // 0
+
+  /// Additional supported protocols (ALPN protocol identifiers)
+  ALPN {
+    ids: Vec<Vec<u8>>,
+  }, // 1
+
+  /// Default set of protocols is not supported
+  NoDefaultALPN, // 2
+
+  /// Alternative port for the service
+  Port {
+    port: u16,
+  }, // 3
+
+  IPv4Hint {
+    addrs: Vec<Ipv4Addr>,
+  }, // 4
+
+  /// Encrypted ClientHello configuration list
+  ECH {
+    config: Vec<u8>,
+  }, // 5
+
+  IPv6Hint {
+    addrs: Vec<Ipv6Addr>,
+  }, // 6
+}
+
+impl SvcParam {
+  /// Returns the key of the parameter
+  pub fn key(&self) -> SvcParamKey {
+    match self {
+      Self::Unknown { key, .. } => SvcParamKey::from(*key),
+      Self::Mandatory { .. } => SvcParamKey::Mandatory,
+      Self::ALPN { .. } => SvcParamKey::ALPN,
+      Self::NoDefaultALPN => SvcParamKey::NoDefaultALPN,
+      Self::Port { .. } => SvcParamKey::Port,
+      Self::IPv4Hint { .. } => SvcParamKey::IPv4Hint,
+      Self::ECH { .. } => SvcParamKey::ECH,
+      Self::IPv6Hint { .. } => SvcParamKey::IPv6Hint,
+    }
+  }
+
+  /// Checks that the parameters of a single record are well-formed according to RFC9460 :-
+  /// - keys appear in strictly increasing order (which also rules out duplicates) and the reserved key 65535 is not used
+  /// - `mandatory` is not empty, doesn't list itself, lists its keys in strictly increasing order and every listed key is present
+  pub fn validate(params: &[SvcParam]) -> Result<(), DrasilDNSError> {
+    let invalid = |msg: &str| Err(DrasilDNSError::InvalidSvcParams { msg: msg.to_string() });
+    let keys: Vec<u16> = params.iter().map(|p| p.key().into()).collect();
+
+    if keys.windows(2).any(|w| w[0] >= w[1]) {
+      return invalid("keys must appear in strictly increasing order");
+    }
+
+    if keys.contains(&SvcParamKey::InvalidKey.into()) {
+      return invalid("key 65535 is reserved");
+    }
+
+    if let Some(SvcParam::Mandatory { keys: mandatory }) = params.first() {
+      let mandatory: Vec<u16> = mandatory.iter().map(|&k| k.into()).collect();
+
+      if mandatory.is_empty() {
+        return invalid("mandatory must list at least one key");
+      }
+      if mandatory.contains(&SvcParamKey::Mandatory.into()) {
+        return invalid("mandatory must not list itself");
+      }
+      if mandatory.windows(2).any(|w| w[0] >= w[1]) {
+        return invalid("keys in mandatory must appear in strictly increasing order");
+      }
+      if mandatory.iter().any(|k| !keys.contains(k)) {
+        return invalid("keys listed in mandatory must be present");
+      }
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn parse(buff: &mut Buffer) -> Result<SvcParam, DrasilDNSError> {
+    buff.read_transaction(|buff| {
+      let key = buff.read_u16()?;
+      let len = buff.read_u16()?;
+      let data = buff.read_bytes(len as usize)?.to_vec();
+      let invalid_length = Err(DrasilDNSError::InvalidSvcParamLength { key, size: len });
+
+      Ok(match SvcParamKey::from(key) {
+        SvcParamKey::Mandatory => {
+          if len == 0 || len % 2 != 0 {
+            return invalid_length;
+          }
+
+          let keys = data.chunks(2)
+            .map(|k| SvcParamKey::from(u16::from_be_bytes([k[0], k[1]])))
+            .collect();
+          Self::Mandatory { keys }
+        },
+
+        SvcParamKey::ALPN => {
+          let mut b: Buffer = data.into();
+          let mut ids = vec![];
+
+          while !b.is_eof() {
+            let id = b.read_character_string()?;
+            if id.is_empty() {
+              return invalid_length;
+            }
+            ids.push(id);
+          }
+
+          if ids.is_empty() {
+            return invalid_length;
+          }
+          Self::ALPN { ids }
+        },
+
+        SvcParamKey::NoDefaultALPN => {
+          if len != 0 {
+            return invalid_length;
+          }
+          Self::NoDefaultALPN
+        },
+
+        SvcParamKey::Port => {
+          if len != 2 {
+            return invalid_length;
+          }
+          Self::Port { port: u16::from_be_bytes([data[0], data[1]]) }
+        },
+
+        SvcParamKey::IPv4Hint => {
+          if len == 0 || len % 4 != 0 {
+            return invalid_length;
+          }
+
+          let addrs = data.chunks(4)
+            .map(|a| Ipv4Addr::new(a[0], a[1], a[2], a[3]))
+            .collect();
+          Self::IPv4Hint { addrs }
+        },
+
+        SvcParamKey::ECH => Self::ECH { config: data },
+
+        SvcParamKey::IPv6Hint => {
+          if len == 0 || len % 16 != 0 {
+            return invalid_length;
+          }
+
+          let addrs = data.chunks(16)
+            .map(|a| {
+              let mut octets = [0_u8; 16];
+              octets.copy_from_slice(a);
+              Ipv6Addr::from(octets)
+            })
+            .collect();
+          Self::IPv6Hint { addrs }
+        },
+
+        SvcParamKey::Unknown(_) | SvcParamKey::InvalidKey => Self::Unknown { key, data },
+      })
+    })
+  }
+
+  pub(crate) fn write_bytes(&self, buff: &mut Buffer) -> Result<(), DrasilDNSError> {
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
+
+    b.write_u16(self.key().into())?;
+    let pos = b.pos();
+    b.write_u16(0)?;
+
+    match self {
+      SvcParam::Unknown { data, .. } => b.write_bytes(data)?,
+      SvcParam::Mandatory { keys } => {
+        for key in keys {
+          b.write_u16((*key).into())?;
+        }
+      },
+      SvcParam::ALPN { ids } => {
+        for id in ids {
+          b.write_character_string(id)?;
+        }
+      },
+      SvcParam::NoDefaultALPN => {},
+      SvcParam::Port { port } => b.write_u16(*port)?,
+      SvcParam::IPv4Hint { addrs } => {
+        for addr in addrs {
+          b.write_bytes(&addr.octets())?;
+        }
+      },
+      SvcParam::ECH { config } => b.write_bytes(config)?,
+      SvcParam::IPv6Hint { addrs } => {
+        for addr in addrs {
+          b.write_bytes(&addr.octets())?;
+        }
+      },
+    }
+
+    let len = b.pos() - (pos + 2);
+    if len > u16::MAX as usize {
+      return Err(DrasilDNSError::InvalidSvcParamLength { key: self.key().into(), size: u16::MAX });
+    }
+    b.patch_bytes(pos, &(len as u16).to_be_bytes())?;
+
+    buff.write_buffer(&b)?;
+    Ok(())
+  }
+}
+
+impl fmt::Display for SvcParam {
+  /// Writes the parameter as `key=value` (RFC9460 section 2.1), lists are comma separated
+  /// and values are written without quotes by escaping special octets
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let value = match self {
+      Self::Unknown { data, .. } => presentation::fmt_escaped(data, false),
+      Self::Mandatory { keys } => keys.iter().map(|k| k.to_string()).collect::<Vec<_>>().join(","),
+      Self::ALPN { ids } => ids.iter().map(|id| presentation::fmt_escaped(id, true)).collect::<Vec<_>>().join(","),
+      Self::NoDefaultALPN => String::new(),
+      Self::Port { port } => port.to_string(),
+      Self::IPv4Hint { addrs } => addrs.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(","),
+      Self::ECH { config } => presentation::fmt_base64(config),
+      Self::IPv6Hint { addrs } => addrs.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(","),
+    };
+
+    match value.is_empty() {
+      true => write!(f, "{}", self.key()),
+      false => write!(f, "{}={}", self.key(), value),
+    }
+  }
+}
+
+impl FromStr for SvcParam {
+  type Err = DrasilDNSError;
+
+  /// Parses a parameter in `key=value` form, escapes are resolved and a missing value is the same as an empty one
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (key, value) = s.split_once('=').unwrap_or((s, ""));
+    let key = SvcParamKey::from_str(key)?;
+    let invalid = |msg: &str| DrasilDNSError::InvalidSvcParams { msg: format!("{} \"{}\"", msg, s) };
+
+    let list = |value: &str| -> Result<Vec<Vec<u8>>, DrasilDNSError> {
+      presentation::split_list(value).into_iter()
+        .map(presentation::unescape)
+        .collect()
+    };
+    let text_list = |value: &str| -> Result<Vec<String>, DrasilDNSError> {
+      list(value)?.into_iter()
+        .map(|item| String::from_utf8(item).map_err(|_| invalid("invalid list item in")))
+        .collect()
+    };
+
+    Ok(match key {
+      SvcParamKey::Mandatory => Self::Mandatory {
+        keys: text_list(value)?.iter().map(|k| k.parse()).collect::<Result<_, _>>()?,
+      },
+
+      SvcParamKey::ALPN => {
+        let ids = list(value)?;
+        if ids.iter().any(|id| id.is_empty() || id.len() > 255) {
+          return Err(invalid("invalid ALPN identifier in"));
+        }
+        Self::ALPN { ids }
+      },
+
+      SvcParamKey::NoDefaultALPN => {
+        if !value.is_empty() {
+          return Err(invalid("no value expected in"));
+        }
+        Self::NoDefaultALPN
+      },
+
+      SvcParamKey::Port => Self::Port { port: value.parse().map_err(|_| invalid("invalid port in"))? },
+
+      SvcParamKey::IPv4Hint => Self::IPv4Hint {
+        addrs: text_list(value)?.iter().map(|a| a.parse().map_err(|_| invalid("invalid address in"))).collect::<Result<_, _>>()?,
+      },
+
+      SvcParamKey::ECH => Self::ECH { config: presentation::parse_base64(value)? },
+
+      SvcParamKey::IPv6Hint => Self::IPv6Hint {
+        addrs: text_list(value)?.iter().map(|a| a.parse().map_err(|_| invalid("invalid address in"))).collect::<Result<_, _>>()?,
+      },
+
+      SvcParamKey::Unknown(_) | SvcParamKey::InvalidKey => Self::Unknown { key: key.into(), data: presentation::unescape(value)? },
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn svc_param_rw() {
+    let mut b = Buffer::with_capacity(0);
+    b.set_expandable(true);
+
+    let params: Vec<SvcParam> = vec![
+      SvcParam::Mandatory { keys: vec![SvcParamKey::ALPN, SvcParamKey::Port] },
+      SvcParam::ALPN { ids: vec![b"h2".to_vec(), b"h3".to_vec()] },
+      SvcParam::NoDefaultALPN,
+      SvcParam::Port { port: 8443 },
+      SvcParam::IPv4Hint { addrs: vec![Ipv4Addr::new(192, 0, 2, 1), Ipv4Addr::new(192, 0, 2, 2)] },
+      SvcParam::ECH { config: vec![0, 1, 2, 3] },
+      SvcParam::IPv6Hint { addrs: vec![Ipv6Addr::LOCALHOST] },
+      SvcParam::Unknown { key: 667, data: b"hello".to_vec() },
+    ];
+
+    SvcParam::validate(&params).expect("Valid params rejected");
+
+    for param in &params {
+      param.write_bytes(&mut b).expect("Failed to write SvcParam");
+    }
+
+    b.seek(0);
+
+    let mut params_after_read = vec![];
+    for _ in 0..params.len() {
+      params_after_read.push(SvcParam::parse(&mut b).expect("Failed to read SvcParam"));
+    }
+
+    assert_eq!(params, params_after_read, "SvcParams unequal after write+read");
+  }
+
+  #[test]
+  fn svc_param_validation() {
+    let port = SvcParam::Port { port: 443 };
+    let alpn = SvcParam::ALPN { ids: vec![b"h2".to_vec()] };
+
+    assert!(SvcParam::validate(&[port.clone(), alpn.clone()]).is_err(), "Unordered keys accepted");
+    assert!(SvcParam::validate(&[port.clone(), port.clone()]).is_err(), "Duplicate keys accepted");
+
+    let mandatory = |keys: Vec<SvcParamKey>| SvcParam::Mandatory { keys };
+    assert!(SvcParam::validate(&[mandatory(vec![SvcParamKey::Port]), port.clone()]).is_ok(), "Valid mandatory rejected");
+    assert!(SvcParam::validate(&[mandatory(vec![]), port.clone()]).is_err(), "Empty mandatory accepted");
+    assert!(SvcParam::validate(&[mandatory(vec![SvcParamKey::Mandatory]), port.clone()]).is_err(), "Self-listing mandatory accepted");
+    assert!(SvcParam::validate(&[mandatory(vec![SvcParamKey::ALPN]), port.clone()]).is_err(), "Missing mandatory key accepted");
+    assert!(SvcParam::validate(&[mandatory(vec![SvcParamKey::Port, SvcParamKey::ALPN]), alpn, port]).is_err(), "Unordered mandatory keys accepted");
+
+    let mut b: Buffer = vec![0, 3, 0, 1, 0].into();
+    assert!(SvcParam::parse(&mut b).is_err(), "Port with invalid length accepted");
+  }
+
+  #[test]
+  fn svc_param_presentation() {
+    let params = [
+      ("mandatory=alpn,port", SvcParam::Mandatory { keys: vec![SvcParamKey::ALPN, SvcParamKey::Port] }),
+      ("alpn=h2,h3\\\\\\044x",SvcParam::ALPN { ids: vec![b"h2".to_vec(), b"h3\\,x".to_vec()] }),
+      ("no-default-alpn", SvcParam::NoDefaultALPN),
+      ("ipv6hint=::1,2001:db8::1", SvcParam::IPv6Hint { addrs: vec![Ipv6Addr::LOCALHOST, "2001:db8::1".parse().unwrap()] }),
+      ("key667=hello\\032world", SvcParam::Unknown { key: 667, data: b"hello world".to_vec() }),
+      ("key667", SvcParam::Unknown { key: 667, data: vec![] }),
+    ];
+
+    for (text, param) in params {
+      assert_eq!(text.parse::<SvcParam>().expect("Failed to parse SvcParam"), param, "SvcParam not parsed correctly");
+      assert_eq!(param.to_string(), text, "SvcParam not displayed correctly");
+    }
+
+    assert_eq!("key3=443".parse::<SvcParam>().unwrap(), SvcParam::Port { port: 443 }, "Known key in key<n> form not parsed");
+    assert_eq!("alpn=a\\,b".parse::<SvcParam>().unwrap(), SvcParam::ALPN { ids: vec![b"a,b".to_vec()] }, "Escaped comma not parsed");
+    assert!("port=".parse::<SvcParam>().is_err(), "Empty port accepted");
+    assert!("alpn=h2,,h3".parse::<SvcParam>().is_err(), "Empty ALPN identifier accepted");
+    assert!("key+1=a".parse::<SvcParam>().is_err(), "Invalid key accepted");
+  }
+}
@@ -0,0 +1,338 @@
+// ===== Imports =====
+use std::net::{Ipv4Addr, Ipv6Addr};
+use proptest::{
+  collection::{hash_set, vec},
+  option,
+  prelude::*,
+  sample::select,
+};
+use crate::{
+  header::{Header, Opcode, RequestKind, ResponseCode},
+  name::{Name, MAX_LABEL_LENGTH, MAX_NAME_LENGTH},
+  packet::Packet,
+  question::Question,
+  record::{
+    Record,
+    caa::CAAValue,
+    edns::{EDNSOption, EDNSOptionType},
+    svcb::SvcParam,
+  },
+  types::{RecordClass, RecordType},
+};
+// ===================
+
+fn bytes(max: usize) -> impl Strategy<Value = Vec<u8>> {
+  vec(any::<u8>(), 0..=max)
+}
+
+fn character_string() -> impl Strategy<Value = Vec<u8>> {
+  bytes(255)
+}
+
+/// Generates valid names, labels are dropped once the name would exceed 255 octets
+pub fn name() -> impl Strategy<Value = Name> {
+  vec(vec(any::<u8>(), 1..=MAX_LABEL_LENGTH), 0..=6).prop_map(|labels| {
+    let mut size = 1;
+    let labels = labels.into_iter().take_while(|label| {
+      size += label.len() + 1;
+      size <= MAX_NAME_LENGTH
+    });
+
+    Name::from_labels(labels).expect("labels are valid and fit in a name")
+  })
+}
+
+pub fn record_class() -> impl Strategy<Value = RecordClass> {
+  any::<u16>().prop_map(RecordClass::from)
+}
+
+pub fn record_type() -> impl Strategy<Value = RecordType> {
+  any::<u16>().prop_map(RecordType::from)
+}
+
+pub fn edns_option() -> impl Strategy<Value = EDNSOption> {
+  let unknown_code = any::<u16>().prop_filter("known option code", |&code| matches!(EDNSOptionType::from(code), EDNSOptionType::Unknown(_)));
+
+  // the address is stored in the smallest integer covering the source netmask
+  let client_subnet = prop_oneof![Just((1_u16, 32_u8)), Just((2, 128))]
+    .prop_flat_map(|(family, max)| (Just(family), 0..=max, 0..=max, any::<u128>()))
+    .prop_map(|(family, source_netmask, scope_netmask, addr)| {
+      let bits = match source_netmask {
+        0..=8 => 8,
+        9..=16 => 16,
+        17..=32 => 32,
+        33..=64 => 64,
+        _ => 128,
+      };
+      let addr = if bits == 128 { addr } else { addr & ((1 << bits) - 1) };
+      EDNSOption::ClientSubnet { family, source_netmask, scope_netmask, addr }
+    });
+
+  prop_oneof![
+    (unknown_code, bytes(64)).prop_map(|(code, data)| EDNSOption::Unknown { code, len: data.len() as u16, data }),
+    "\\PC{0,32}".prop_map(|data| EDNSOption::NSID { data }),
+    client_subnet,
+    (any::<u64>(), option::of(any::<u64>())).prop_map(|(client, server)| EDNSOption::Cookie { client, server }),
+    any::<u16>().prop_map(|timeout| EDNSOption::KeepAlive { timeout }),
+    (0..=512_u16).prop_map(|len| EDNSOption::Padding { len }),
+    (any::<u16>(), any::<u16>()).prop_map(|(flags, qname_min_length)| EDNSOption::ChainQuery { flags, qname_min_length }),
+    vec(any::<u16>(), 0..16).prop_map(|tags| EDNSOption::KeyTag { tags }),
+    (any::<u16>(), "\\PC{0,32}").prop_map(|(info_code, extra_text)| EDNSOption::EDE { info_code, extra_text }),
+    (0..=32_u8, 0..=32_u8, any::<u32>()).prop_map(|(source_netmask, scope_netmask, addr)| EDNSOption::EcsIPv4 { source_netmask, scope_netmask, addr }),
+    (0..=128_u8, 0..=128_u8, any::<u128>()).prop_map(|(source_netmask, scope_netmask, addr)| EDNSOption::EcsIPv6 { source_netmask, scope_netmask, addr }),
+  ]
+}
+
+/// Generates parameters of a single SVCB/HTTPS record, which always pass `SvcParam::validate`
+pub fn svc_params() -> impl Strategy<Value = Vec<SvcParam>> {
+  let param = prop_oneof![
+    (7..u16::MAX, bytes(32)).prop_map(|(key, data)| SvcParam::Unknown { key, data }),
+    vec(vec(any::<u8>(), 1..=32), 1..4).prop_map(|ids| SvcParam::ALPN { ids }),
+    Just(SvcParam::NoDefaultALPN),
+    any::<u16>().prop_map(|port| SvcParam::Port { port }),
+    vec(any::<[u8; 4]>().prop_map(Ipv4Addr::from), 1..4).prop_map(|addrs| SvcParam::IPv4Hint { addrs }),
+    bytes(64).prop_map(|config| SvcParam::ECH { config }),
+    vec(any::<[u8; 16]>().prop_map(Ipv6Addr::from), 1..4).prop_map(|addrs| SvcParam::IPv6Hint { addrs }),
+  ];
+
+  (vec(param, 0..6), any::<bool>()).prop_map(|(mut params, mandatory)| {
+    params.sort_by_key(|p| u16::from(p.key()));
+    params.dedup_by_key(|p| u16::from(p.key()));
+
+    if mandatory && !params.is_empty() {
+      let keys = params.iter().map(|p| p.key()).collect();
+      params.insert(0, SvcParam::Mandatory { keys });
+    }
+    params
+  })
+}
+
+/// Generates the raw tag and value of CAA properties along with their parsed form,
+/// both well-formed values of the known tags (in any case) and arbitrary ones
+pub fn caa_property() -> impl Strategy<Value = (Vec<u8>, Vec<u8>, CAAValue)> {
+  let alnum: Vec<u8> = (b'a'..=b'z').chain(b'A'..=b'Z').chain(b'0'..=b'9').collect();
+  let tag = prop_oneof![
+    select(vec![&b"issue"[..], b"ISSUE", b"issuewild", b"IssueWild", b"issuemail", b"iodef"]).prop_map(|tag| tag.to_vec()),
+    vec(select(alnum), 1..=15),
+  ];
+  let value = prop_oneof![
+    "(ca[a-z0-9]{0,8}\\.[a-z]{2,4})? {0,2}(; {0,2}[a-z]{1,5}=[a-z0-9]{1,8}){0,2}".prop_map(String::into_bytes),
+    bytes(64),
+  ];
+
+  (tag, value).prop_map(|(tag, value)| {
+    let property = CAAValue::parse(&tag, &value).expect("tag is a non-empty alphanumeric string");
+    (tag, value, property)
+  })
+}
+
+/// Generates records of every variant
+pub fn record() -> impl Strategy<Value = Record> {
+  let unknown_type = any::<u16>().prop_filter("known record type", |&v| RecordType::from(v).is_unknown());
+  let type_bitmaps = || hash_set(record_type(), 0..16);
+
+  prop_oneof![
+    (name(), any::<u32>(), unknown_type, record_class(), bytes(64))
+      .prop_map(|(domain, ttl, record_type, class, data)| Record::Unknown { domain, ttl, len: data.len() as u16, record_type, class, data }),
+    (name(), any::<[u8; 4]>(), any::<u32>(), record_class())
+      .prop_map(|(domain, addr, ttl, class)| Record::A { domain, addr: Ipv4Addr::from(addr), ttl, class }),
+    (name(), name(), any::<u32>(), record_class())
+      .prop_map(|(domain, host, ttl, class)| Record::NS { domain, host, ttl, class }),
+    (name(), name(), any::<u32>(), record_class())
+      .prop_map(|(domain, host, ttl, class)| Record::CNAME { domain, host, ttl, class }),
+    (name(), name(), name(), any::<[u32; 5]>(), any::<u32>(), record_class())
+      .prop_map(|(domain, mname, rname, [serial, refresh, retry, expire, minimum], ttl, class)| {
+        Record::SOA { domain, mname, rname, serial, refresh, retry, expire, minimum, ttl, class }
+      }),
+    (name(), bytes(64), any::<u32>(), record_class())
+      .prop_map(|(domain, data, ttl, class)| Record::NULL { domain, data, ttl, class }),
+    (name(), name(), any::<u32>(), record_class())
+      .prop_map(|(domain, host, ttl, class)| Record::PTR { domain, host, ttl, class }),
+    (name(), character_string(), character_string(), any::<u32>(), record_class())
+      .prop_map(|(domain, cpu, os, ttl, class)| Record::HINFO { domain, cpu, os, ttl, class }),
+    (name(), any::<u16>(), name(), any::<u32>(), record_class())
+      .prop_map(|(domain, priority, host, ttl, class)| Record::MX { domain, priority, host, ttl, class }),
+    (name(), vec(character_string(), 0..4), any::<u32>(), record_class())
+      .prop_map(|(domain, strings, ttl, class)| Record::TXT { domain, strings, ttl, class }),
+    (name(), any::<[u8; 16]>(), any::<u32>(), record_class())
+      .prop_map(|(domain, addr, ttl, class)| Record::AAAA { domain, addr: Ipv6Addr::from(addr), ttl, class }),
+    (name(), any::<[u16; 3]>(), name(), any::<u32>(), record_class())
+      .prop_map(|(domain, [priority, weight, port], target, ttl, class)| Record::SRV { domain, priority, weight, port, target, ttl, class }),
+    (name(), any::<[u16; 2]>(), [character_string(), character_string(), character_string()], name(), any::<u32>(), record_class())
+      .prop_map(|(domain, [order, preference], [flags, services, regexp], replacement, ttl, class)| {
+        Record::NAPTR { domain, order, preference, flags, services, regexp, replacement, ttl, class }
+      }),
+    (name(), name(), any::<u32>(), record_class())
+      .prop_map(|(domain, target, ttl, class)| Record::DNAME { domain, target, ttl, class }),
+    (any::<u16>(), any::<u8>(), any::<u8>(), any::<bool>(), vec(edns_option(), 0..4))
+      .prop_map(|(udp_payload_size, extended_rcode, version, dnssec_ok, options)| {
+        Record::OPT { udp_payload_size, extended_rcode, version, dnssec_ok, options }
+      }),
+    (name(), record_class(), any::<u32>(), any::<u16>(), any::<u8>(), any::<u8>(), bytes(64))
+      .prop_map(|(domain, class, ttl, key_tag, algorithm, digest_type, digest)| {
+        Record::DS { domain, class, ttl, key_tag, algorithm: algorithm.into(), digest_type: digest_type.into(), digest }
+      }),
+    (name(), record_class(), any::<u32>(), any::<u8>(), any::<u8>(), bytes(64))
+      .prop_map(|(domain, class, ttl, algorithm, fingerprint_type, fingerprint)| {
+        Record::SSHFP { domain, class, ttl, algorithm: algorithm.into(), fingerprint_type: fingerprint_type.into(), fingerprint }
+      }),
+    (name(), record_class(), any::<u32>(), any::<u16>(), any::<u8>(), any::<u8>(), any::<[u32; 3]>(), any::<u16>(), name(), bytes(128))
+      .prop_map(|(domain, class, ttl, type_covered, algorithm, labels, [original_ttl, signature_expiration, signature_inception], key_tag, signer_name, signature)| {
+        Record::RRSIG {
+          domain,
+          class,
+          ttl,
+          type_covered,
+          algorithm: algorithm.into(),
+          labels,
+          original_ttl,
+          signature_expiration,
+          signature_inception,
+          key_tag,
+          signer_name,
+          signature,
+        }
+      }),
+    (name(), record_class(), any::<u32>(), name(), type_bitmaps())
+      .prop_map(|(domain, class, ttl, next_domain_name, record_types)| Record::NSEC { domain, class, ttl, next_domain_name, record_types }),
+    (name(), record_class(), any::<u32>(), any::<u16>(), any::<u8>(), any::<u8>(), bytes(128))
+      .prop_map(|(domain, class, ttl, flags, protocol, algorithm, public_key)| {
+        Record::DNSKEY { domain, class, ttl, flags, protocol, algorithm: algorithm.into(), public_key }
+      }),
+    (name(), record_class(), any::<u32>(), any::<u8>(), any::<u8>(), any::<u16>(), bytes(255), bytes(255), type_bitmaps())
+      .prop_map(|(domain, class, ttl, hash_algorithm, flags, iterations, salt, next_hashed_owner_name, record_types)| {
+        Record::NSEC3 {
+          domain,
+          class,
+          ttl,
+          hash_algorithm,
+          flags,
+          iterations,
+          salt_length: salt.len() as u8,
+          salt,
+          hash_length: next_hashed_owner_name.len() as u8,
+          next_hashed_owner_name,
+          record_types,
+        }
+      }),
+    (name(), record_class(), any::<u32>(), any::<u8>(), any::<u8>(), any::<u16>(), bytes(255))
+      .prop_map(|(domain, class, ttl, hash_algorithm, flags, iterations, salt)| {
+        Record::NSEC3PARAM { domain, class, ttl, hash_algorithm, flags, iterations, salt_length: salt.len() as u8, salt }
+      }),
+    (name(), record_class(), any::<u32>(), any::<[u8; 3]>(), bytes(64), any::<bool>())
+      .prop_map(|(domain, class, ttl, [cert_usage, selector, matching_type], data, smimea)| {
+        let (cert_usage, selector, matching_type) = (cert_usage.into(), selector.into(), matching_type.into());
+        match smimea {
+          false => Record::TLSA { domain, class, ttl, cert_usage, selector, matching_type, data },
+          true => Record::SMIMEA { domain, class, ttl, cert_usage, selector, matching_type, data },
+        }
+      }),
+    (name(), record_class(), any::<u32>(), bytes(128))
+      .prop_map(|(domain, class, ttl, public_key)| Record::OPENPGPKEY { domain, class, ttl, public_key }),
+    (name(), any::<u16>(), name(), svc_params(), any::<u32>(), record_class(), any::<bool>())
+      .prop_map(|(domain, priority, target, params, ttl, class, https)| match https {
+        false => Record::SVCB { domain, priority, target, params, ttl, class },
+        true => Record::HTTPS { domain, priority, target, params, ttl, class },
+      }),
+    (name(), any::<[u16; 2]>(), bytes(64), any::<u32>(), record_class())
+      .prop_map(|(domain, [priority, weight], target, ttl, class)| Record::URI { domain, priority, weight, target, ttl, class }),
+    (name(), record_class(), any::<u32>(), any::<u8>(), caa_property())
+      .prop_map(|(domain, class, ttl, flags, (tag, value, property))| Record::CAA { domain, class, ttl, flags, tag, value, property }),
+  ]
+}
+
+pub fn question() -> impl Strategy<Value = Question> {
+  (name(), record_type(), record_class())
+    .prop_map(|(name, record_type, record_class)| Question { name, record_type, record_class })
+}
+
+/// Generates headers with all section counts set to 0
+pub fn header() -> impl Strategy<Value = Header> {
+  (any::<u16>(), any::<bool>(), 0..16_u8, any::<[bool; 7]>(), 0..16_u16).prop_map(|(id, response, opcode, flags, response_code)| {
+    let [is_authoritative_answer, is_truncated_message, is_recursion_desired, is_recursion_available, z, authentic_data, checking_disabled] = flags;
+
+    Header {
+      id,
+      request_kind: if response { RequestKind::Response } else { RequestKind::Query },
+      opcode: Opcode::from(opcode),
+      is_authoritative_answer,
+      is_truncated_message,
+      is_recursion_desired,
+      is_recursion_available,
+      z,
+      authentic_data,
+      checking_disabled,
+      response_code: ResponseCode::from(response_code),
+      question_count: 0,
+      answer_count: 0,
+      authority_count: 0,
+      additional_count: 0,
+    }
+  })
+}
+
+/// Generates packets whose header counts match their sections
+pub fn packet() -> impl Strategy<Value = Packet> {
+  (header(), vec(question(), 0..3), vec(record(), 0..4), vec(record(), 0..4), vec(record(), 0..4))
+    .prop_map(|(mut header, questions, answers, authority, additional)| {
+      header.question_count = questions.len() as u16;
+      header.answer_count = answers.len() as u16;
+      header.authority_count = authority.len() as u16;
+      header.additional_count = additional.len() as u16;
+
+      Packet { header, questions, answers, authority, additional }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::buffer::Buffer;
+
+  proptest! {
+    #[test]
+    fn record_round_trip(record in record()) {
+      let mut b = Buffer::with_capacity(0);
+      b.set_expandable(true);
+      record.write_bytes(&mut b).expect("Failed to write record");
+
+      let data: Vec<u8> = b.into();
+      let mut b: Buffer = data.into();
+      let record_after_read = Record::parse(&mut b).expect("Failed to read record");
+      prop_assert_eq!(Some(record), record_after_read, "Record unequal after write+read");
+      prop_assert!(b.is_eof(), "Record not read entirely");
+    }
+
+    #[test]
+    fn record_presentation_round_trip(record in record()) {
+      let record_after_parse: Record = record.to_string().parse().expect("Failed to parse record");
+      prop_assert_eq!(record, record_after_parse, "Record unequal after display+parse");
+    }
+
+    #[test]
+    fn question_presentation_round_trip(question in question()) {
+      let question_after_parse: Question = question.to_string().parse().expect("Failed to parse question");
+      prop_assert_eq!(question, question_after_parse, "Question unequal after display+parse");
+    }
+
+    #[test]
+    fn edns_option_round_trip(option in edns_option()) {
+      let mut b = Buffer::with_capacity(0);
+      b.set_expandable(true);
+      option.write_bytes(&mut b).expect("Failed to write EDNS option");
+
+      let data: Vec<u8> = b.into();
+      let mut b: Buffer = data.into();
+      let option_after_read = EDNSOption::parse(&mut b).expect("Failed to read EDNS option");
+      prop_assert_eq!(option, option_after_read, "EDNS option unequal after write+read");
+      prop_assert!(b.is_eof(), "EDNS option not read entirely");
+    }
+
+    #[test]
+    fn packet_round_trip(packet in packet()) {
+      let data = packet.to_bytes().expect("Failed to write packet");
+      let packet_after_read = Packet::parse(&data).expect("Failed to read packet");
+      prop_assert_eq!(packet, packet_after_read, "Packet unequal after write+read");
+    }
+  }
+}
@@ -1,25 +1,36 @@
 
 pub mod dnssec;
+pub mod dane;
 
 // ===== Imports =====
-use std::collections::HashSet;
+use std::{collections::{BTreeMap, HashSet}, fmt, str::FromStr};
 use crate::{buffer::Buffer, error::DrasilDNSError};
 // ===================
 
 /// # Record Class
 /// Enum representing record class value in records and questions
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum RecordClass {
   Unknown(u16),
   IN = 1,
+  CH = 3, // Chaos
+  HS = 4, // Hesiod
+  NONE = 254, // used in updates (RFC2136)
+  ANY = 255, // only valid in questions
 }
 
-impl Into<u16> for RecordClass {
-  fn into(self) -> u16 {
-    match self {
-      Self::IN => 1,
-      Self::Unknown(v) => v,
+impl From<RecordClass> for u16 {
+  fn from(value: RecordClass) -> Self {
+    match value {
+      RecordClass::IN => 1,
+      RecordClass::CH => 3,
+      RecordClass::HS => 4,
+      RecordClass::NONE => 254,
+      RecordClass::ANY => 255,
+      RecordClass::Unknown(v) => v,
     }
   }
 }
@@ -28,47 +39,128 @@ impl From<u16> for RecordClass {
   fn from(value: u16) -> Self {
     match value {
       1 => Self::IN,
+      3 => Self::CH,
+      4 => Self::HS,
+      254 => Self::NONE,
+      255 => Self::ANY,
       v => Self::Unknown(v),
     }
   }
 }
 
+impl fmt::Display for RecordClass {
+  /// Writes the mnemonic of the class, or the `CLASS<n>` form of RFC3597 for unknown classes
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::IN => f.write_str("IN"),
+      Self::CH => f.write_str("CH"),
+      Self::HS => f.write_str("HS"),
+      Self::NONE => f.write_str("NONE"),
+      Self::ANY => f.write_str("ANY"),
+      Self::Unknown(v) => write!(f, "CLASS{}", v),
+    }
+  }
+}
+
+impl FromStr for RecordClass {
+  type Err = DrasilDNSError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let upper = s.to_ascii_uppercase();
+    match upper.as_str() {
+      "IN" => Ok(Self::IN),
+      "CH" => Ok(Self::CH),
+      "HS" => Ok(Self::HS),
+      "NONE" => Ok(Self::NONE),
+      "ANY" => Ok(Self::ANY),
+      _ => upper.strip_prefix("CLASS")
+        .and_then(|v| v.parse::<u16>().ok())
+        .map(Self::from)
+        .ok_or_else(|| DrasilDNSError::InvalidData { msg: format!("unknown record class \"{}\"", s) }),
+    }
+  }
+}
+
 /// # Record Type
 /// Enum representing record type value in records and questions
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum RecordType {
   Unknown(u16),
   A = 1,
   NS = 2,
   CNAME = 5,
+  SOA = 6,
+  NULL = 10,
+  PTR = 12,
+  HINFO = 13,
   MX = 15,
+  TXT = 16,
   AAAA = 28,
+  SRV = 33,
+  NAPTR = 35,
+  DNAME = 39,
   OPT = 41, // used for eDNS
   DS = 43,
+  SSHFP = 44,
   RRSIG = 46,
   NSEC = 47,
   DNSKEY = 48,
   NSEC3 = 50,
   NSEC3PARAM = 51,
+  TLSA = 52,
+  SMIMEA = 53,
+  OPENPGPKEY = 61,
+  SVCB = 64,
+  HTTPS = 65,
+  IXFR = 251, // query types (RFC1035 section 3.2.3, RFC1995)
+  AXFR = 252,
+  MAILB = 253,
+  MAILA = 254,
+  ANY = 255,
+  URI = 256,
+  CAA = 257,
 }
 
-impl Into<u16> for RecordType {
-  fn into(self) -> u16 {
-    match self {
-      Self::A => 1,
-      Self::NS => 2,
-      Self::CNAME => 5,
-      Self::MX => 15,
-      Self::AAAA => 28,
-      Self::OPT => 41,
-      Self::DS => 43,
-      Self::RRSIG => 46,
-      Self::NSEC => 47,
-      Self::DNSKEY => 48,
-      Self::NSEC3 => 50,
-      Self::NSEC3PARAM => 51,
-      Self::Unknown(v) => v,
+impl From<RecordType> for u16 {
+  fn from(value: RecordType) -> Self {
+    match value {
+      RecordType::A => 1,
+      RecordType::NS => 2,
+      RecordType::CNAME => 5,
+      RecordType::SOA => 6,
+      RecordType::NULL => 10,
+      RecordType::PTR => 12,
+      RecordType::HINFO => 13,
+      RecordType::MX => 15,
+      RecordType::TXT => 16,
+      RecordType::AAAA => 28,
+      RecordType::SRV => 33,
+      RecordType::NAPTR => 35,
+      RecordType::DNAME => 39,
+      RecordType::OPT => 41,
+      RecordType::DS => 43,
+      RecordType::SSHFP => 44,
+      RecordType::RRSIG => 46,
+      RecordType::NSEC => 47,
+      RecordType::DNSKEY => 48,
+      RecordType::NSEC3 => 50,
+      RecordType::NSEC3PARAM => 51,
+      RecordType::TLSA => 52,
+      RecordType::SMIMEA => 53,
+      RecordType::OPENPGPKEY => 61,
+      RecordType::SVCB => 64,
+      RecordType::HTTPS => 65,
+      RecordType::IXFR => 251,
+      RecordType::AXFR => 252,
+      RecordType::MAILB => 253,
+      RecordType::MAILA => 254,
+      RecordType::ANY => 255,
+      RecordType::URI => 256,
+      RecordType::CAA => 257,
+      RecordType::Unknown(v) => v,
     }
   }
 }
@@ -79,53 +171,159 @@ impl From<u16> for RecordType {
       1 => Self::A,
       2 => Self::NS,
       5 => Self::CNAME,
+      6 => Self::SOA,
+      10 => Self::NULL,
+      12 => Self::PTR,
+      13 => Self::HINFO,
       15 => Self::MX,
+      16 => Self::TXT,
       28 => Self::AAAA,
+      33 => Self::SRV,
+      35 => Self::NAPTR,
+      39 => Self::DNAME,
       41 => Self::OPT,
       43 => Self::DS,
+      44 => Self::SSHFP,
       46 => Self::RRSIG,
       47 => Self::NSEC,
       48 => Self::DNSKEY,
       50 => Self::NSEC3,
       51 => Self::NSEC3PARAM,
+      52 => Self::TLSA,
+      53 => Self::SMIMEA,
+      61 => Self::OPENPGPKEY,
+      64 => Self::SVCB,
+      65 => Self::HTTPS,
+      251 => Self::IXFR,
+      252 => Self::AXFR,
+      253 => Self::MAILB,
+      254 => Self::MAILA,
+      255 => Self::ANY,
+      256 => Self::URI,
+      257 => Self::CAA,
       v => Self::Unknown(v),
     }
   }
 }
 
+impl fmt::Display for RecordType {
+  /// Writes the mnemonic of the type, or the `TYPE<n>` form of RFC3597 for unknown types
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mnemonic = match self {
+      Self::A => "A",
+      Self::NS => "NS",
+      Self::CNAME => "CNAME",
+      Self::SOA => "SOA",
+      Self::NULL => "NULL",
+      Self::PTR => "PTR",
+      Self::HINFO => "HINFO",
+      Self::MX => "MX",
+      Self::TXT => "TXT",
+      Self::AAAA => "AAAA",
+      Self::SRV => "SRV",
+      Self::NAPTR => "NAPTR",
+      Self::DNAME => "DNAME",
+      Self::OPT => "OPT",
+      Self::DS => "DS",
+      Self::SSHFP => "SSHFP",
+      Self::RRSIG => "RRSIG",
+      Self::NSEC => "NSEC",
+      Self::DNSKEY => "DNSKEY",
+      Self::NSEC3 => "NSEC3",
+      Self::NSEC3PARAM => "NSEC3PARAM",
+      Self::TLSA => "TLSA",
+      Self::SMIMEA => "SMIMEA",
+      Self::OPENPGPKEY => "OPENPGPKEY",
+      Self::SVCB => "SVCB",
+      Self::HTTPS => "HTTPS",
+      Self::IXFR => "IXFR",
+      Self::AXFR => "AXFR",
+      Self::MAILB => "MAILB",
+      Self::MAILA => "MAILA",
+      Self::ANY => "ANY",
+      Self::URI => "URI",
+      Self::CAA => "CAA",
+      Self::Unknown(v) => return write!(f, "TYPE{}", v),
+    };
+    f.write_str(mnemonic)
+  }
+}
+
+impl FromStr for RecordType {
+  type Err = DrasilDNSError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let upper = s.to_ascii_uppercase();
+    match upper.as_str() {
+      "A" => Ok(Self::A),
+      "NS" => Ok(Self::NS),
+      "CNAME" => Ok(Self::CNAME),
+      "SOA" => Ok(Self::SOA),
+      "NULL" => Ok(Self::NULL),
+      "PTR" => Ok(Self::PTR),
+      "HINFO" => Ok(Self::HINFO),
+      "MX" => Ok(Self::MX),
+      "TXT" => Ok(Self::TXT),
+      "AAAA" => Ok(Self::AAAA),
+      "SRV" => Ok(Self::SRV),
+      "NAPTR" => Ok(Self::NAPTR),
+      "DNAME" => Ok(Self::DNAME),
+      "OPT" => Ok(Self::OPT),
+      "DS" => Ok(Self::DS),
+      "SSHFP" => Ok(Self::SSHFP),
+      "RRSIG" => Ok(Self::RRSIG),
+      "NSEC" => Ok(Self::NSEC),
+      "DNSKEY" => Ok(Self::DNSKEY),
+      "NSEC3" => Ok(Self::NSEC3),
+      "NSEC3PARAM" => Ok(Self::NSEC3PARAM),
+      "TLSA" => Ok(Self::TLSA),
+      "SMIMEA" => Ok(Self::SMIMEA),
+      "OPENPGPKEY" => Ok(Self::OPENPGPKEY),
+      "SVCB" => Ok(Self::SVCB),
+      "HTTPS" => Ok(Self::HTTPS),
+      "IXFR" => Ok(Self::IXFR),
+      "AXFR" => Ok(Self::AXFR),
+      "MAILB" => Ok(Self::MAILB),
+      "MAILA" => Ok(Self::MAILA),
+      "ANY" => Ok(Self::ANY),
+      "URI" => Ok(Self::URI),
+      "CAA" => Ok(Self::CAA),
+      _ => upper.strip_prefix("TYPE")
+        .and_then(|v| v.parse::<u16>().ok())
+        .map(Self::from)
+        .ok_or_else(|| DrasilDNSError::InvalidData { msg: format!("unknown record type \"{}\"", s) }),
+    }
+  }
+}
+
 impl RecordType {
   pub fn is_unknown(&self) -> bool {
     if let Self::Unknown(_) = self {
       return true;
     }
-    return false;
+    false
   }
 
+  /// Parses the type bitmaps of NSEC and NSEC3 records (RFC4034 section 4.1.2).
+  /// The most significant bit of the first octet of a window stands for the first type of that window.
   pub(crate) fn parse_type_bitmaps(mut buff: Buffer) -> Result<HashSet<RecordType>, DrasilDNSError> {
     let mut recs = HashSet::new();
 
     while !buff.is_eof() {
       let window = buff.read_u8()?;
       let len = buff.read_u8()?;
+      if len == 0 || len > 32 {
+        return Err(DrasilDNSError::InvalidData { msg: format!("invalid type bitmap length ({})", len) });
+      }
+
       let bitmap = buff.read_bytes(len as usize)?;
       let window_offset = window as u16 * 256;
 
       for (byte_offset, &byte) in bitmap.iter().enumerate() {
-        if byte == 0 {
-          continue;
-        }
-
-        let byte_offset = byte_offset as u16 * 8;
-        let mut pos = 1;
-
-        while pos <= 8 {
-          if (byte >> (8 - pos)) & 0b1 == 0b1 {
-            let rec: RecordType = RecordType::from(window_offset + byte_offset + pos);
-            if !rec.is_unknown() {
-              recs.insert(rec);
-            }
+        for bit in 0..8 {
+          if (byte >> (7 - bit)) & 0b1 == 0b1 {
+            recs.insert(RecordType::from(window_offset + byte_offset as u16 * 8 + bit));
           }
-          pos += 1;
         }
       }
     }
@@ -133,36 +331,30 @@ impl RecordType {
     Ok(recs)
   }
 
-  pub(crate) fn into_type_bitmaps(set: &HashSet<RecordType>) -> Result<Buffer, DrasilDNSError> {
+  /// Writes the type bitmaps of NSEC and NSEC3 records (RFC4034 section 4.1.2), windows are written in increasing order.
+  pub(crate) fn into_type_bitmaps(set: &HashSet<RecordType>) -> Result<Buffer<'static>, DrasilDNSError> {
     let mut buff = Buffer::with_capacity(0);
     buff.set_expandable(true);
 
-    let mut windows: Vec<Vec<u8>> = vec![vec![]];
+    let mut windows: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
 
     for &rec in set {
       let rec_val: u16 = rec.into();
-      let window = ((rec_val - 1) / 256) as usize;
-      let offset = ((rec_val - 1) % 256) as usize;
-      let byte_offset = offset / 8;
-      let byte_pos = offset % 8;
+      let [window, offset] = rec_val.to_be_bytes();
+      let byte_offset = (offset / 8) as usize;
 
-      if windows.get(window).is_none() {
-        windows.push(vec![]);
+      let bitmap = windows.entry(window).or_default();
+      if bitmap.len() <= byte_offset {
+        bitmap.resize(byte_offset + 1, 0);
       }
 
-      if windows[window].get(byte_offset).is_none() {
-        windows[window].resize(byte_offset + 1, 0);
-      }
-
-      windows[window][byte_offset] |= 1 << (7 - byte_pos);
+      bitmap[byte_offset] |= 1 << (7 - (offset % 8));
     }
 
-    for (window, bitmap) in windows.iter().enumerate() {
-      if !bitmap.is_empty() {
-        buff.write_u8(window as u8)?;
-        buff.write_u8(bitmap.len() as u8)?;
-        buff.write_bytes(&bitmap)?;
-      }
+    for (window, bitmap) in &windows {
+      buff.write_u8(*window)?;
+      buff.write_u8(bitmap.len() as u8)?;
+      buff.write_bytes(bitmap)?;
     }
 
     Ok(buff)
@@ -181,18 +373,28 @@ mod tests {
     set.insert(RecordType::NSEC3PARAM);
 
     let ans: Vec<u8> = vec![
-      0, 7, 0b1000_1000, 0, 0, 0, 0, 0, 0b00100000,
+      0, 7, 0b0100_0100, 0, 0, 0, 0, 0, 0b0001_0000,
     ];
 
     let buff = RecordType::into_type_bitmaps(&set).expect("Failed at into_type_bitmaps");
     let buff_bytes: Vec<u8> = buff.into();
     assert_eq!(buff_bytes, ans, "RecordType set to Type-Bitmaps conversion failed");
+
+    // example from RFC4034 section 4.3
+    let set: HashSet<RecordType> = [RecordType::A, RecordType::MX, RecordType::RRSIG, RecordType::NSEC, RecordType::Unknown(1234)].into();
+    let mut ans: Vec<u8> = vec![0x00, 0x06, 0x40, 0x01, 0x00, 0x00, 0x00, 0x03, 0x04, 0x1b];
+    ans.extend_from_slice(&[0; 26]);
+    ans.push(0x20);
+
+    let buff_bytes: Vec<u8> = RecordType::into_type_bitmaps(&set).expect("Failed at into_type_bitmaps").into();
+    assert_eq!(buff_bytes, ans, "RFC4034 Type-Bitmaps example not written correctly");
+    assert_eq!(RecordType::parse_type_bitmaps(ans[..].into()).expect("Failed at parse_type_bitmaps"), set, "RFC4034 Type-Bitmaps example not parsed correctly");
   }
 
   #[test]
   fn type_bitmaps_to_record_type_set() {
     let data = [
-      0, 1, 0b1000_1000,
+      0, 1, 0b0100_0100,
     ];
 
     let buff: Buffer = data[..].into();
@@ -201,4 +403,67 @@ mod tests {
     assert!(set.contains(&RecordType::A), "set doesn't contain type A which is set");
     assert!(set.contains(&RecordType::CNAME), "set doesn't contain type CNAME which is set");
   }
+
+  #[test]
+  fn type_and_class_presentation() {
+    assert_eq!(RecordType::NSEC3PARAM.to_string(), "NSEC3PARAM");
+    assert_eq!(RecordType::Unknown(65280).to_string(), "TYPE65280");
+    assert_eq!("nsec3param".parse::<RecordType>().unwrap(), RecordType::NSEC3PARAM);
+    assert_eq!("TYPE1".parse::<RecordType>().unwrap(), RecordType::A, "Known type in TYPE<n> form not parsed");
+    assert!("TYPE65536".parse::<RecordType>().is_err(), "Out of range type accepted");
+
+    assert_eq!(RecordClass::Unknown(5).to_string(), "CLASS5");
+    assert_eq!("class1".parse::<RecordClass>().unwrap(), RecordClass::IN);
+    for (mnemonic, value) in [("IN", 1), ("CH", 3), ("HS", 4), ("NONE", 254), ("ANY", 255)] {
+      let class: RecordClass = mnemonic.parse().expect("Failed to parse class mnemonic");
+      assert_eq!(u16::from(class), value, "Incorrect value for class {}", mnemonic);
+      assert_eq!(RecordClass::from(value).to_string(), mnemonic, "Class {} not equal after write+read", mnemonic);
+    }
+    for (mnemonic, value) in [("IXFR", 251), ("AXFR", 252), ("MAILB", 253), ("MAILA", 254), ("ANY", 255)] {
+      let record_type: RecordType = mnemonic.to_ascii_lowercase().parse().expect("Failed to parse type mnemonic");
+      assert_eq!(u16::from(record_type), value, "Incorrect value for type {}", mnemonic);
+      assert_eq!(RecordType::from(value).to_string(), mnemonic, "Type {} not equal after write+read", mnemonic);
+    }
+    assert!("XX".parse::<RecordClass>().is_err(), "Unknown class mnemonic accepted");
+
+    assert_eq!(dnssec::DNSSECAlgorithm::RSASHA1.to_string(), "RSASHA1");
+    assert_eq!("5".parse::<dnssec::DNSSECAlgorithm>().unwrap(), dnssec::DNSSECAlgorithm::RSASHA1);
+    assert_eq!("200".parse::<dnssec::DNSSECAlgorithm>().unwrap().to_string(), "200");
+    assert_eq!(dnssec::DNSSECAlgorithm::from(7).to_string(), "RSASHA1-NSEC3-SHA1");
+    assert_eq!("ecc-gost12".parse::<dnssec::DNSSECAlgorithm>().unwrap(), dnssec::DNSSECAlgorithm::ECCGOST12);
+  }
+
+  #[test]
+  fn dnssec_registries() {
+    use dnssec::{DNSSECAlgorithm, DNSSECDigestType};
+
+    for v in 0..=u8::MAX {
+      assert_eq!(u8::from(DNSSECAlgorithm::from(v)), v, "Algorithm {} not preserved", v);
+      assert_eq!(u8::from(DNSSECDigestType::from(v)), v, "Digest type {} not preserved", v);
+    }
+    for v in [8, 13, 15] {
+      assert!(DNSSECAlgorithm::from(v).mnemonic().is_some(), "Algorithm {} unknown", v);
+    }
+
+    assert!(DNSSECAlgorithm::ECDSAP256SHA256.is_zone_signing() && !DNSSECAlgorithm::ECDSAP256SHA256.is_deprecated());
+    assert!(DNSSECAlgorithm::RSASHA1.is_zone_signing() && DNSSECAlgorithm::RSASHA1.is_deprecated());
+    assert!(!DNSSECAlgorithm::DH.is_zone_signing() && !DNSSECAlgorithm::Unknown(99).is_zone_signing());
+    assert!(DNSSECDigestType::SHA1.is_deprecated() && !DNSSECDigestType::SHA256.is_deprecated());
+    assert_eq!(DNSSECDigestType::SHA384.digest_len(), Some(48));
+    assert_eq!(DNSSECDigestType::GOST12.mnemonic(), Some("GOST R 34.11-2012"));
+
+    assert_eq!(DNSSECAlgorithm::ED25519.public_key_len(), Some(32));
+    assert_eq!(DNSSECAlgorithm::ED448.signature_len(), Some(114));
+    assert_eq!(DNSSECAlgorithm::ECDSAP384SHA384.key_size(&[0; 96]), Some(384));
+
+    // RFC3110 encoding: exponent length, exponent, modulus
+    let mut key = vec![3, 1, 0, 1, 0x7F];
+    key.extend([0xFF; 127]);
+    assert_eq!(DNSSECAlgorithm::RSASHA256.key_size(&key), Some(1023), "Incorrect RSA key size");
+    let mut key = vec![0, 0, 3, 1, 0, 1, 0x80];
+    key.extend([0; 255]);
+    assert_eq!(DNSSECAlgorithm::RSASHA512.key_size(&key), Some(2048), "Incorrect RSA key size with long exponent length");
+    assert_eq!(DNSSECAlgorithm::RSASHA256.key_size(&[3, 1, 0]), None, "Truncated RSA key accepted");
+    assert_eq!(DNSSECAlgorithm::DSA.key_size(&[8]), Some(1024));
+  }
 }
\ No newline at end of file
@@ -0,0 +1,229 @@
+/// # TLSA Certificate Usage
+/// According to RFC6698 :-
+///
+/// > A one-octet value, called "certificate usage", specifies the provided
+/// > association that will be used to match the certificate presented in
+/// > the TLS handshake.
+///
+/// Same values are used by SMIMEA records (RFC8162).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum TLSACertUsage {
+  PKIXTA = 0, // CA constraint
+  PKIXEE = 1, // Service certificate constraint
+  DANETA = 2, // Trust anchor assertion
+  DANEEE = 3, // Domain-issued certificate
+  Unknown(u8),
+  PRIVCERT = 255, // Private use
+}
+
+impl From<TLSACertUsage> for u8 {
+  fn from(value: TLSACertUsage) -> Self {
+    match value {
+      TLSACertUsage::PKIXTA => 0,
+      TLSACertUsage::PKIXEE => 1,
+      TLSACertUsage::DANETA => 2,
+      TLSACertUsage::DANEEE => 3,
+      TLSACertUsage::PRIVCERT => 255,
+      TLSACertUsage::Unknown(v) => v,
+    }
+  }
+}
+
+impl From<u8> for TLSACertUsage {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => Self::PKIXTA,
+      1 => Self::PKIXEE,
+      2 => Self::DANETA,
+      3 => Self::DANEEE,
+      255 => Self::PRIVCERT,
+      v => Self::Unknown(v),
+    }
+  }
+}
+
+/// # TLSA Selector
+/// According to RFC6698 :-
+/// > A one-octet value, called "selector", specifies which part of the TLS
+/// > certificate presented by the server will be matched against the
+/// > association data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum TLSASelector {
+  CERT = 0, // Full certificate
+  SPKI = 1, // SubjectPublicKeyInfo
+  Unknown(u8),
+  PRIVSEL = 255, // Private use
+}
+
+impl From<TLSASelector> for u8 {
+  fn from(value: TLSASelector) -> Self {
+    match value {
+      TLSASelector::CERT => 0,
+      TLSASelector::SPKI => 1,
+      TLSASelector::PRIVSEL => 255,
+      TLSASelector::Unknown(v) => v,
+    }
+  }
+}
+
+impl From<u8> for TLSASelector {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => Self::CERT,
+      1 => Self::SPKI,
+      255 => Self::PRIVSEL,
+      v => Self::Unknown(v),
+    }
+  }
+}
+
+/// # TLSA Matching Type
+/// According to RFC6698 :-
+/// > A one-octet value, called "matching type", specifies how the
+/// > certificate association is presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum TLSAMatchingType {
+  FULL = 0, // Exact match on selected content
+  SHA256 = 1, // SHA-256 hash of selected content
+  SHA512 = 2, // SHA-512 hash of selected content
+  Unknown(u8),
+  PRIVMATCH = 255, // Private use
+}
+
+impl From<TLSAMatchingType> for u8 {
+  fn from(value: TLSAMatchingType) -> Self {
+    match value {
+      TLSAMatchingType::FULL => 0,
+      TLSAMatchingType::SHA256 => 1,
+      TLSAMatchingType::SHA512 => 2,
+      TLSAMatchingType::PRIVMATCH => 255,
+      TLSAMatchingType::Unknown(v) => v,
+    }
+  }
+}
+
+impl From<u8> for TLSAMatchingType {
+  fn from(value: u8) -> Self {
+    match value {
+      0 => Self::FULL,
+      1 => Self::SHA256,
+      2 => Self::SHA512,
+      255 => Self::PRIVMATCH,
+      v => Self::Unknown(v),
+    }
+  }
+}
+
+/// # SSHFP Algorithm
+/// According to RFC4255 :-
+/// > This algorithm number octet describes the algorithm of the public
+/// > key.
+///
+/// Later values were added by RFC6594, RFC7479 and RFC8709.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum SSHFPAlgorithm {
+  Unknown(u8),
+  RSA = 1,
+  DSA = 2,
+  ECDSA = 3,
+  ED25519 = 4,
+  ED448 = 6,
+}
+
+impl From<SSHFPAlgorithm> for u8 {
+  fn from(value: SSHFPAlgorithm) -> Self {
+    match value {
+      SSHFPAlgorithm::RSA => 1,
+      SSHFPAlgorithm::DSA => 2,
+      SSHFPAlgorithm::ECDSA => 3,
+      SSHFPAlgorithm::ED25519 => 4,
+      SSHFPAlgorithm::ED448 => 6,
+      SSHFPAlgorithm::Unknown(v) => v,
+    }
+  }
+}
+
+impl From<u8> for SSHFPAlgorithm {
+  fn from(value: u8) -> Self {
+    match value {
+      1 => Self::RSA,
+      2 => Self::DSA,
+      3 => Self::ECDSA,
+      4 => Self::ED25519,
+      6 => Self::ED448,
+      v => Self::Unknown(v),
+    }
+  }
+}
+
+/// # SSHFP Fingerprint Type
+/// According to RFC4255 :-
+/// > The fingerprint type octet describes the message-digest algorithm
+/// > used to calculate the fingerprint of the public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum SSHFPFingerprintType {
+  Unknown(u8),
+  SHA1 = 1, // SHA-1
+  SHA256 = 2, // SHA-256 (RFC6594)
+}
+
+impl From<SSHFPFingerprintType> for u8 {
+  fn from(value: SSHFPFingerprintType) -> Self {
+    match value {
+      SSHFPFingerprintType::SHA1 => 1,
+      SSHFPFingerprintType::SHA256 => 2,
+      SSHFPFingerprintType::Unknown(v) => v,
+    }
+  }
+}
+
+impl From<u8> for SSHFPFingerprintType {
+  fn from(value: u8) -> Self {
+    match value {
+      1 => Self::SHA1,
+      2 => Self::SHA256,
+      v => Self::Unknown(v),
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dane_types_rw() {
+    assert_eq!(TLSACertUsage::from(3), TLSACertUsage::DANEEE);
+    assert_eq!(TLSASelector::from(1), TLSASelector::SPKI);
+    assert_eq!(TLSAMatchingType::from(2), TLSAMatchingType::SHA512);
+    assert_eq!(SSHFPAlgorithm::from(6), SSHFPAlgorithm::ED448);
+    assert_eq!(SSHFPFingerprintType::from(2), SSHFPFingerprintType::SHA256);
+    assert_eq!(TLSACertUsage::from(255), TLSACertUsage::PRIVCERT);
+    assert_eq!(SSHFPAlgorithm::from(5), SSHFPAlgorithm::Unknown(5), "Unassigned value not kept as unknown");
+
+    // every value maps back to itself, known or not
+    for v in 0..=u8::MAX {
+      assert_eq!(u8::from(TLSACertUsage::from(v)), v, "TLSA certificate usage {} not equal after read+write", v);
+      assert_eq!(u8::from(TLSASelector::from(v)), v, "TLSA selector {} not equal after read+write", v);
+      assert_eq!(u8::from(TLSAMatchingType::from(v)), v, "TLSA matching type {} not equal after read+write", v);
+      assert_eq!(u8::from(SSHFPAlgorithm::from(v)), v, "SSHFP algorithm {} not equal after read+write", v);
+      assert_eq!(u8::from(SSHFPFingerprintType::from(v)), v, "SSHFP fingerprint type {} not equal after read+write", v);
+    }
+  }
+}
@@ -1,4 +1,9 @@
 
+// ===== Imports =====
+use std::{fmt, str::FromStr};
+use crate::error::DrasilDNSError;
+// ===================
+
 /// # DNSSEC Algorithm
 /// According to RFC4034 :-
 /// 
@@ -11,32 +16,60 @@
 /// > both.  Those usable for zone signing may appear in DNSKEY, RRSIG, and
 /// > DS RRs.  Those usable for transaction security would be present in
 /// > SIG(0) and KEY RRs, as described in RFC2931.
+///
+/// Covers the IANA "DNS Security Algorithm Numbers" registry.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum DNSSECAlgorithm {
-  Unknown(u8),
+  DELETE = 0, // Delete DS (RFC8078)
   RSAMD5 = 1, // RSA MD5
   DH = 2, // Diffie-Hellman
   DSA = 3, // DSA/SH-1
   ECC = 4, // Elliptic Curve
   RSASHA1 = 5, // RSA SHA-1
+  DSANSEC3SHA1 = 6, // DSA-NSEC3-SHA1 (RFC5155)
+  RSASHA1NSEC3SHA1 = 7, // RSASHA1-NSEC3-SHA1 (RFC5155)
+  RSASHA256 = 8, // RSA SHA-256 (RFC5702)
+  RSASHA512 = 10, // RSA SHA-512 (RFC5702)
+  ECCGOST = 12, // GOST R 34.10-2001 (RFC5933)
+  ECDSAP256SHA256 = 13, // ECDSA Curve P-256 with SHA-256 (RFC6605)
+  ECDSAP384SHA384 = 14, // ECDSA Curve P-384 with SHA-384 (RFC6605)
+  ED25519 = 15, // Ed25519 (RFC8080)
+  ED448 = 16, // Ed448 (RFC8080)
+  SM2SM3 = 17, // SM2 signing with SM3 hashing (RFC9563)
+  ECCGOST12 = 23, // GOST R 34.10-2012 (RFC9558)
   INDIRECT = 252,
   PRIVATEDNS = 253,
   PRIVATEOID = 254,
+  Unknown(u8),
 }
 
-impl Into<u8> for DNSSECAlgorithm {
-  fn into(self) -> u8 {
-    match self {
-      Self::RSAMD5 => 1,
-      Self::DH => 2,
-      Self::DSA => 3,
-      Self::ECC => 4,
-      Self::RSASHA1 => 5,
-      Self::INDIRECT => 252,
-      Self::PRIVATEDNS => 253,
-      Self::PRIVATEOID => 254,
-      Self::Unknown(val) => val,
+impl From<DNSSECAlgorithm> for u8 {
+  fn from(value: DNSSECAlgorithm) -> Self {
+    match value {
+      DNSSECAlgorithm::DELETE => 0,
+      DNSSECAlgorithm::RSAMD5 => 1,
+      DNSSECAlgorithm::DH => 2,
+      DNSSECAlgorithm::DSA => 3,
+      DNSSECAlgorithm::ECC => 4,
+      DNSSECAlgorithm::RSASHA1 => 5,
+      DNSSECAlgorithm::DSANSEC3SHA1 => 6,
+      DNSSECAlgorithm::RSASHA1NSEC3SHA1 => 7,
+      DNSSECAlgorithm::RSASHA256 => 8,
+      DNSSECAlgorithm::RSASHA512 => 10,
+      DNSSECAlgorithm::ECCGOST => 12,
+      DNSSECAlgorithm::ECDSAP256SHA256 => 13,
+      DNSSECAlgorithm::ECDSAP384SHA384 => 14,
+      DNSSECAlgorithm::ED25519 => 15,
+      DNSSECAlgorithm::ED448 => 16,
+      DNSSECAlgorithm::SM2SM3 => 17,
+      DNSSECAlgorithm::ECCGOST12 => 23,
+      DNSSECAlgorithm::INDIRECT => 252,
+      DNSSECAlgorithm::PRIVATEDNS => 253,
+      DNSSECAlgorithm::PRIVATEOID => 254,
+      DNSSECAlgorithm::Unknown(val) => val,
     }
   }
 }
@@ -44,11 +77,23 @@ impl Into<u8> for DNSSECAlgorithm {
 impl From<u8> for DNSSECAlgorithm {
   fn from(value: u8) -> Self {
     match value {
+      0 => Self::DELETE,
       1 => Self::RSAMD5,
       2 => Self::DH,
       3 => Self::DSA,
       4 => Self::ECC,
       5 => Self::RSASHA1,
+      6 => Self::DSANSEC3SHA1,
+      7 => Self::RSASHA1NSEC3SHA1,
+      8 => Self::RSASHA256,
+      10 => Self::RSASHA512,
+      12 => Self::ECCGOST,
+      13 => Self::ECDSAP256SHA256,
+      14 => Self::ECDSAP384SHA384,
+      15 => Self::ED25519,
+      16 => Self::ED448,
+      17 => Self::SM2SM3,
+      23 => Self::ECCGOST12,
       252 => Self::INDIRECT,
       253 => Self::PRIVATEDNS,
       254 => Self::PRIVATEOID,
@@ -57,22 +102,182 @@ impl From<u8> for DNSSECAlgorithm {
   }
 }
 
+/// All algorithms with a mnemonic, used to parse them
+const ALGORITHMS: [DNSSECAlgorithm; 20] = [
+  DNSSECAlgorithm::DELETE,
+  DNSSECAlgorithm::RSAMD5,
+  DNSSECAlgorithm::DH,
+  DNSSECAlgorithm::DSA,
+  DNSSECAlgorithm::ECC,
+  DNSSECAlgorithm::RSASHA1,
+  DNSSECAlgorithm::DSANSEC3SHA1,
+  DNSSECAlgorithm::RSASHA1NSEC3SHA1,
+  DNSSECAlgorithm::RSASHA256,
+  DNSSECAlgorithm::RSASHA512,
+  DNSSECAlgorithm::ECCGOST,
+  DNSSECAlgorithm::ECDSAP256SHA256,
+  DNSSECAlgorithm::ECDSAP384SHA384,
+  DNSSECAlgorithm::ED25519,
+  DNSSECAlgorithm::ED448,
+  DNSSECAlgorithm::SM2SM3,
+  DNSSECAlgorithm::ECCGOST12,
+  DNSSECAlgorithm::INDIRECT,
+  DNSSECAlgorithm::PRIVATEDNS,
+  DNSSECAlgorithm::PRIVATEOID,
+];
+
+impl DNSSECAlgorithm {
+  /// Returns the mnemonic of the algorithm as listed in the IANA registry, `None` for unassigned values
+  pub fn mnemonic(&self) -> Option<&'static str> {
+    Some(match self {
+      Self::DELETE => "DELETE",
+      Self::RSAMD5 => "RSAMD5",
+      Self::DH => "DH",
+      Self::DSA => "DSA",
+      Self::ECC => "ECC",
+      Self::RSASHA1 => "RSASHA1",
+      Self::DSANSEC3SHA1 => "DSA-NSEC3-SHA1",
+      Self::RSASHA1NSEC3SHA1 => "RSASHA1-NSEC3-SHA1",
+      Self::RSASHA256 => "RSASHA256",
+      Self::RSASHA512 => "RSASHA512",
+      Self::ECCGOST => "ECC-GOST",
+      Self::ECDSAP256SHA256 => "ECDSAP256SHA256",
+      Self::ECDSAP384SHA384 => "ECDSAP384SHA384",
+      Self::ED25519 => "ED25519",
+      Self::ED448 => "ED448",
+      Self::SM2SM3 => "SM2SM3",
+      Self::ECCGOST12 => "ECC-GOST12",
+      Self::INDIRECT => "INDIRECT",
+      Self::PRIVATEDNS => "PRIVATEDNS",
+      Self::PRIVATEOID => "PRIVATEOID",
+      Self::Unknown(_) => return None,
+    })
+  }
+
+  /// Whether the algorithm may be used to sign zones, i.e. appear in DNSKEY, RRSIG and DS records
+  /// ("Zone Signing" column of the IANA registry)
+  pub fn is_zone_signing(&self) -> bool {
+    !matches!(self, Self::DELETE | Self::RSAMD5 | Self::DH | Self::ECC | Self::INDIRECT | Self::Unknown(_))
+  }
+
+  /// Whether RFC8624 (section 3.1) advises against signing with the algorithm, being either "MUST NOT" or "NOT RECOMMENDED".
+  /// Algorithms which can't sign zones at all are not considered deprecated.
+  pub fn is_deprecated(&self) -> bool {
+    matches!(
+      self,
+      Self::RSAMD5 | Self::DSA | Self::RSASHA1 | Self::DSANSEC3SHA1 | Self::RSASHA1NSEC3SHA1 | Self::RSASHA512 | Self::ECCGOST,
+    )
+  }
+
+  /// Whether the algorithm uses RSA keys (RFC3110 encoding), whose size varies
+  pub fn is_rsa(&self) -> bool {
+    matches!(self, Self::RSAMD5 | Self::RSASHA1 | Self::RSASHA1NSEC3SHA1 | Self::RSASHA256 | Self::RSASHA512)
+  }
+
+  /// Returns the length (in octets) of the public key field of DNSKEY records, for algorithms with fixed-size keys
+  pub fn public_key_len(&self) -> Option<usize> {
+    match self {
+      Self::ECDSAP256SHA256 | Self::ECCGOST | Self::ECCGOST12 | Self::SM2SM3 => Some(64),
+      Self::ECDSAP384SHA384 => Some(96),
+      Self::ED25519 => Some(32),
+      Self::ED448 => Some(57),
+      _ => None,
+    }
+  }
+
+  /// Returns the length (in octets) of the signature field of RRSIG records, for algorithms with fixed-size signatures
+  pub fn signature_len(&self) -> Option<usize> {
+    match self {
+      Self::ECDSAP256SHA256 | Self::ECCGOST | Self::ECCGOST12 | Self::SM2SM3 | Self::ED25519 => Some(64),
+      Self::ECDSAP384SHA384 => Some(96),
+      Self::ED448 => Some(114),
+      _ => None,
+    }
+  }
+
+  /// Returns the size (in bits) of the key given in the public key field of a DNSKEY record.
+  /// For RSA keys this is the size of the modulus (RFC3110 section 2), for DSA keys it follows from the T parameter
+  /// (RFC2536 section 2). Returns `None` if the key is malformed or the size is unknown for the algorithm.
+  pub fn key_size(&self, public_key: &[u8]) -> Option<usize> {
+    match self {
+      _ if self.is_rsa() => {
+        let (exponent_len, rest) = match public_key.split_first()? {
+          (0, rest) => (u16::from_be_bytes([*rest.first()?, *rest.get(1)?]) as usize, rest.get(2..)?),
+          (&len, rest) => (len as usize, rest),
+        };
+        let modulus = rest.get(exponent_len..)?;
+        let modulus = &modulus[modulus.iter().take_while(|&&b| b == 0).count()..];
+
+        let first = *modulus.first()?;
+        Some(modulus.len() * 8 - first.leading_zeros() as usize)
+      },
+      Self::DSA | Self::DSANSEC3SHA1 => {
+        let t = *public_key.first()? as usize;
+        (t <= 8).then_some(512 + 64 * t)
+      },
+      Self::ECDSAP256SHA256 | Self::ECCGOST | Self::ECCGOST12 | Self::SM2SM3 | Self::ED25519 => Some(256),
+      Self::ECDSAP384SHA384 => Some(384),
+      Self::ED448 => Some(456),
+      _ => None,
+    }
+  }
+}
+
+impl fmt::Display for DNSSECAlgorithm {
+  /// Writes the mnemonic of the algorithm (RFC4034 appendix A.1), or its number if it has none
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.mnemonic() {
+      Some(mnemonic) => f.write_str(mnemonic),
+      None => write!(f, "{}", u8::from(*self)),
+    }
+  }
+}
+
+impl FromStr for DNSSECAlgorithm {
+  type Err = DrasilDNSError;
+
+  /// Parses either the mnemonic or the number of the algorithm
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Some(algorithm) = ALGORITHMS.iter().find(|a| a.mnemonic().is_some_and(|m| m.eq_ignore_ascii_case(s))) {
+      return Ok(*algorithm);
+    }
+
+    s.parse::<u8>()
+      .map(Self::from)
+      .map_err(|_| DrasilDNSError::InvalidData { msg: format!("unknown DNSSEC algorithm \"{}\"", s) })
+  }
+}
+
 /// # DNSSEC Digest Type
 /// According to RFC4034 :-
 /// > A "Digest Type" field in the DS resource record types identifies the
 /// > cryptographic digest algorithm used by the resource record.
+///
+/// Covers the IANA "Digest Algorithms" registry.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum DNSSECDigestType {
   Unknown(u8),
   SHA1 = 1, // SHA-1
+  SHA256 = 2, // SHA-256 (RFC4509)
+  GOST = 3, // GOST R 34.11-94 (RFC5933)
+  SHA384 = 4, // SHA-384 (RFC6605)
+  GOST12 = 5, // GOST R 34.11-2012 (RFC9558)
+  SM3 = 6, // SM3 (RFC9563)
 }
 
-impl Into<u8> for DNSSECDigestType {
-  fn into(self) -> u8 {
-    match self {
-      Self::SHA1 => 1,
-      Self::Unknown(v) => v,
+impl From<DNSSECDigestType> for u8 {
+  fn from(value: DNSSECDigestType) -> Self {
+    match value {
+      DNSSECDigestType::SHA1 => 1,
+      DNSSECDigestType::SHA256 => 2,
+      DNSSECDigestType::GOST => 3,
+      DNSSECDigestType::SHA384 => 4,
+      DNSSECDigestType::GOST12 => 5,
+      DNSSECDigestType::SM3 => 6,
+      DNSSECDigestType::Unknown(v) => v,
     }
   }
 }
@@ -81,7 +286,42 @@ impl From<u8> for DNSSECDigestType {
   fn from(value: u8) -> Self {
     match value {
       1 => Self::SHA1,
+      2 => Self::SHA256,
+      3 => Self::GOST,
+      4 => Self::SHA384,
+      5 => Self::GOST12,
+      6 => Self::SM3,
       v => Self::Unknown(v),
     }
   }
-}
\ No newline at end of file
+}
+
+impl DNSSECDigestType {
+  /// Returns the name of the digest algorithm as listed in the IANA registry, `None` for unassigned values
+  pub fn mnemonic(&self) -> Option<&'static str> {
+    Some(match self {
+      Self::SHA1 => "SHA-1",
+      Self::SHA256 => "SHA-256",
+      Self::GOST => "GOST R 34.11-94",
+      Self::SHA384 => "SHA-384",
+      Self::GOST12 => "GOST R 34.11-2012",
+      Self::SM3 => "SM3",
+      Self::Unknown(_) => return None,
+    })
+  }
+
+  /// Whether RFC8624 (section 3.3) forbids generating DS records with the digest ("MUST NOT")
+  pub fn is_deprecated(&self) -> bool {
+    matches!(self, Self::SHA1 | Self::GOST)
+  }
+
+  /// Returns the length (in octets) of the digests produced by the algorithm
+  pub fn digest_len(&self) -> Option<usize> {
+    match self {
+      Self::SHA1 => Some(20),
+      Self::SHA256 | Self::GOST | Self::GOST12 | Self::SM3 => Some(32),
+      Self::SHA384 => Some(48),
+      Self::Unknown(_) => None,
+    }
+  }
+}
@@ -0,0 +1,417 @@
+
+// ===== Imports =====
+use std::{fmt::Write, fs, path::{Path, PathBuf}};
+use crate::{
+  error::DrasilDNSError,
+  name::Name,
+  presentation::{self, Entry, Token, Tokens},
+  record::Record,
+  types::{RecordClass, RecordType},
+};
+// ===================
+
+/// Maximum nesting of `$INCLUDE` directives, guards against include loops
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Maximum number of records a single `$GENERATE` directive may produce
+const MAX_GENERATE_RECORDS: u64 = 65536;
+
+/// Maximum field width of a `$GENERATE` substitution, the longest a label can be
+const MAX_GENERATE_WIDTH: usize = 255;
+
+/// # Zone Parser
+/// Reads master files (RFC1035 section 5) into records. Supported are the `$ORIGIN`, `$TTL` (RFC2308), `$INCLUDE`
+/// and `$GENERATE` (as known from BIND) directives, multi-line entries in parentheses, comments, relative names
+/// and owner names, TTLs and classes inherited from the previous entry.
+///
+/// When an entry has no TTL, the `$TTL` value is used, or else the TTL of the last entry which had one.
+#[derive(Debug, Clone, Default)]
+pub struct ZoneParser {
+  origin: Option<Name>,
+  default_ttl: Option<u32>,
+  base_dir: Option<PathBuf>,
+}
+
+/// State carried from one entry of a master file to the next
+#[derive(Debug, Clone)]
+struct State {
+  origin: Option<Name>,
+  default_ttl: Option<u32>,
+  last_ttl: Option<u32>,
+  last_class: RecordClass,
+  last_owner: Option<Name>,
+}
+
+fn syntax(token: Option<&Token>, entry: &Entry, msg: impl ToString) -> DrasilDNSError {
+  let (line, column) = token.map_or((entry.line, 1), |t| (t.line, t.column));
+  DrasilDNSError::Syntax { line, column, msg: msg.to_string() }
+}
+
+impl ZoneParser {
+  /// Create a new parser without origin and default TTL
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the origin used for relative names until the file sets one using `$ORIGIN`
+  pub fn with_origin(mut self, origin: Name) -> Self {
+    self.origin = Some(origin);
+    self
+  }
+
+  /// Set the TTL used for entries without one until the file sets one using `$TTL`
+  pub fn with_default_ttl(mut self, ttl: u32) -> Self {
+    self.default_ttl = Some(ttl);
+    self
+  }
+
+  /// Set the directory against which relative `$INCLUDE` paths are resolved
+  pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+    self.base_dir = Some(dir.into());
+    self
+  }
+
+  /// Parses the master file text into records, in the order they appear
+  pub fn parse(&self, text: &str) -> Result<Vec<Record>, DrasilDNSError> {
+    let mut state = State {
+      origin: self.origin.clone(),
+      default_ttl: self.default_ttl,
+      last_ttl: None,
+      last_class: RecordClass::IN,
+      last_owner: None,
+    };
+
+    let mut records = vec![];
+    self.parse_text(text, &mut state, 0, &mut records)?;
+    Ok(records)
+  }
+
+  /// Reads and parses the master file at `path`, its directory is the base directory unless one was set
+  pub fn parse_file(&self, path: impl AsRef<Path>) -> Result<Vec<Record>, DrasilDNSError> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)?;
+
+    match &self.base_dir {
+      Some(_) => self.parse(&text),
+      None => self.clone()
+        .with_base_dir(path.parent().unwrap_or(Path::new("")))
+        .parse(&text),
+    }
+  }
+
+  fn parse_text(&self, text: &str, state: &mut State, depth: usize, records: &mut Vec<Record>) -> Result<(), DrasilDNSError> {
+    for entry in presentation::entries(text)? {
+      match entry.tokens.first() {
+        Some(token) if !entry.blank_owner && token.text.starts_with('$') => {
+          self.parse_directive(&entry, state, depth, records)?;
+        },
+        _ => records.push(parse_record(&entry, &entry.tokens, state)?),
+      }
+    }
+
+    Ok(())
+  }
+
+  fn parse_directive(&self, entry: &Entry, state: &mut State, depth: usize, records: &mut Vec<Record>) -> Result<(), DrasilDNSError> {
+    let mut t = Tokens::new(&entry.tokens);
+    let directive = t.next("directive")?;
+    let err = |t: &Tokens, e: DrasilDNSError| syntax(t.last(), entry, e);
+
+    match directive.text.to_ascii_uppercase().as_str() {
+      "$ORIGIN" => {
+        let origin = t.name("origin", state.origin.as_ref()).map_err(|e| err(&t, e))?;
+        t.finish().map_err(|e| err(&t, e))?;
+        state.origin = Some(origin);
+      },
+
+      "$TTL" => {
+        let ttl = t.ttl("TTL").map_err(|e| err(&t, e))?;
+        t.finish().map_err(|e| err(&t, e))?;
+        state.default_ttl = Some(ttl);
+      },
+
+      "$INCLUDE" => {
+        let file = t.next("file name").map_err(|e| err(&t, e))?;
+        let origin = match t.peek() {
+          Some(_) => Some(t.name("origin", state.origin.as_ref()).map_err(|e| err(&t, e))?),
+          None => state.origin.clone(),
+        };
+        t.finish().map_err(|e| err(&t, e))?;
+
+        if depth >= MAX_INCLUDE_DEPTH {
+          return Err(syntax(Some(directive), entry, "too many nested $INCLUDE directives"));
+        }
+
+        let file_name = presentation::unescape(&file.text)
+          .map(|name| String::from_utf8_lossy(&name).to_string())
+          .map_err(|e| syntax(Some(file), entry, e))?;
+        let path = match &self.base_dir {
+          Some(dir) => dir.join(&file_name),
+          None => PathBuf::from(&file_name),
+        };
+
+        let text = fs::read_to_string(&path)
+          .map_err(|e| syntax(Some(file), entry, format!("failed to read {}: {}", path.display(), e)))?;
+
+        // the included file starts with the origin given here and doesn't affect the origin of this one (RFC1035 section 5.1)
+        let mut included = State { origin, ..state.clone() };
+        self.parse_text(&text, &mut included, depth + 1, records)
+          .map_err(|e| syntax(Some(file), entry, format!("in {}: {}", path.display(), e)))?;
+      },
+
+      "$GENERATE" => generate(entry, &mut t, state, records)?,
+
+      _ => return Err(syntax(Some(directive), entry, format!("unknown directive {}", directive.text))),
+    }
+
+    Ok(())
+  }
+}
+
+/// Parses a record entry, whose owner may be inherited from the previous entry
+fn parse_record(entry: &Entry, tokens: &[Token], state: &mut State) -> Result<Record, DrasilDNSError> {
+  let mut t = Tokens::new(tokens);
+  let err = |t: &Tokens, e: DrasilDNSError| syntax(t.last(), entry, e);
+
+  let owner = match entry.blank_owner {
+    true => state.last_owner.clone().ok_or_else(|| syntax(tokens.first(), entry, "no previous owner name to inherit"))?,
+    false => t.name("owner name", state.origin.as_ref()).map_err(|e| err(&t, e))?,
+  };
+
+  let (explicit_ttl, class) = t.ttl_and_class();
+  let ttl = explicit_ttl
+    .or(state.default_ttl)
+    .or(state.last_ttl)
+    .ok_or_else(|| syntax(t.peek(), entry, "no TTL given and no $TTL or previous TTL to use"))?;
+  let class = class.unwrap_or(state.last_class);
+
+  let record_type: RecordType = t.next("record type")
+    .and_then(|token| token.text.parse())
+    .map_err(|e| err(&t, e))?;
+
+  let record = Record::from_rdata_tokens(owner.clone(), ttl, class, record_type, &mut t, state.origin.as_ref())
+    .map_err(|e| err(&t, e))?;
+
+  // only explicit TTLs are remembered, so that $TTL keeps applying to the following entries
+  if explicit_ttl.is_some() {
+    state.last_ttl = explicit_ttl;
+  }
+  state.last_class = class;
+  state.last_owner = Some(owner);
+
+  Ok(record)
+}
+
+/// Expands a `$GENERATE start-stop[/step] lhs [ttl] [class] type rhs` directive.
+/// In `lhs` and `rhs`, `$` is replaced by the iterator and `${offset[,width[,base]]}` formats it
+/// (bases `d`, `o`, `x` and `X`, widths up to 255), `\$` stands for a literal `$`.
+fn generate(entry: &Entry, t: &mut Tokens, state: &mut State, records: &mut Vec<Record>) -> Result<(), DrasilDNSError> {
+  let range = t.next("range").map_err(|e| syntax(t.last(), entry, e))?;
+  let invalid_range = || syntax(Some(range), entry, format!("invalid range \"{}\"", range.text));
+
+  let (bounds, step) = match range.text.split_once('/') {
+    Some((bounds, step)) => (bounds, step.parse::<u64>().map_err(|_| invalid_range())?),
+    None => (range.text.as_str(), 1),
+  };
+  let (start, stop) = bounds.split_once('-').ok_or_else(invalid_range)?;
+  let (start, stop): (u64, u64) = (start.parse().map_err(|_| invalid_range())?, stop.parse().map_err(|_| invalid_range())?);
+
+  if step == 0 || start > stop || stop > u32::MAX as u64 {
+    return Err(invalid_range());
+  }
+  if (stop - start) / step >= MAX_GENERATE_RECORDS {
+    return Err(syntax(Some(range), entry, format!("$GENERATE may produce at most {} records", MAX_GENERATE_RECORDS)));
+  }
+
+  let lhs = t.next("owner name template").map_err(|e| syntax(t.last(), entry, e))?;
+  let rest = t.rest();
+
+  // the templates are the owner name and the RDATA, which follows the optional TTL and class and the type
+  let mut fields = Tokens::new(rest);
+  fields.ttl_and_class();
+  fields.next("record type").map_err(|e| syntax(fields.last(), entry, e))?;
+  let rdata_start = rest.len() - fields.rest().len();
+
+  for i in (start..=stop).step_by(step as usize) {
+    let mut tokens = vec![substitute(lhs, i, entry)?];
+    tokens.extend_from_slice(&rest[..rdata_start]);
+    for token in &rest[rdata_start..] {
+      tokens.push(substitute(token, i, entry)?);
+    }
+
+    records.push(parse_record(entry, &tokens, state)?);
+  }
+
+  Ok(())
+}
+
+/// Replaces the iterator references of a `$GENERATE` template
+fn substitute(token: &Token, i: u64, entry: &Entry) -> Result<Token, DrasilDNSError> {
+  let err = |msg: &str| syntax(Some(token), entry, format!("{} in \"{}\"", msg, token.text));
+  let mut text = String::with_capacity(token.text.len());
+  let mut chars = token.text.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' if chars.peek() == Some(&'$') => {
+        chars.next();
+        text.push_str("\\$");
+      },
+      '\\' => {
+        text.push(c);
+        text.extend(chars.next());
+      },
+      '$' if chars.peek() == Some(&'{') => {
+        chars.next();
+        let spec: String = chars.by_ref().take_while(|&c| c != '}').collect();
+        let mut parts = spec.split(',');
+
+        let offset: i64 = parts.next().unwrap_or_default().parse().map_err(|_| err("invalid offset"))?;
+        let width: usize = match parts.next() {
+          Some(width) => width.parse().ok().filter(|&w| w <= MAX_GENERATE_WIDTH).ok_or_else(|| err("invalid width"))?,
+          None => 0,
+        };
+        let value = i64::try_from(i).ok().and_then(|i| i.checked_add(offset)).ok_or_else(|| err("offset out of range"))?;
+        let value = u64::try_from(value).map_err(|_| err("negative value"))?;
+
+        let formatted = match parts.next().unwrap_or("d") {
+          "d" => format!("{:0width$}", value),
+          "o" => format!("{:0width$o}", value),
+          "x" => format!("{:0width$x}", value),
+          "X" => format!("{:0width$X}", value),
+          _ => return Err(err("unsupported base")),
+        };
+        if parts.next().is_some() {
+          return Err(err("too many modifiers"));
+        }
+        text.push_str(&formatted);
+      },
+      '$' => text.push_str(&i.to_string()),
+      c => text.push(c),
+    }
+  }
+
+  Ok(Token { text, ..token.clone() })
+}
+
+/// Writes the records as a master file in a canonical layout: every record on one line with
+/// its fully qualified owner name, TTL, class and type, SOA records first and the others sorted
+/// by owner name (in canonical order, RFC4034 section 6.1), type and RDATA, with duplicates dropped.
+pub fn write_zone(records: &[Record]) -> Result<String, DrasilDNSError> {
+  let mut lines = vec![];
+
+  for record in records {
+    let rdata = record.rdata()?;
+    let key = (record.record_type() != RecordType::SOA, record.domain().clone(), u16::from(record.record_type()), rdata);
+    lines.push((key, record));
+  }
+
+  lines.sort_by(|a, b| a.0.cmp(&b.0));
+  lines.dedup_by(|a, b| a.0 == b.0);
+
+  let mut zone = String::new();
+  for (_, record) in lines {
+    let _ = write!(zone, "{}\t{}\t{}\t{}", record.domain(), record.ttl(), record.class(), record.record_type());
+
+    let rdata = record.rdata_to_string()?;
+    if !rdata.is_empty() {
+      let _ = write!(zone, "\t{}", rdata);
+    }
+    zone.push('\n');
+  }
+
+  Ok(zone)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::net::Ipv4Addr;
+
+  #[test]
+  fn zone_parse() {
+    let zone = r#"
+$ORIGIN example.com.
+$TTL 1h
+@   IN  SOA ns1 hostmaster (
+          2024010101 ; serial
+          2h 1h 2w 5m )
+    NS  ns1
+    NS  ns2.example.net.
+ns1 300 A 192.0.2.1
+    AAAA 2001:db8::1   ; inherits owner and class, TTL comes from $TTL
+www CNAME @
+$ORIGIN sub
+txt IN 60 TXT "hello world" ( "second"
+  string )
+$GENERATE 1-3/2 host-${10,3} A 10.0.0.$
+"#;
+
+    let records = ZoneParser::new().parse(zone).expect("Failed to parse zone");
+    let expected: Vec<Record> = [
+      "example.com. 3600 IN SOA ns1.example.com. hostmaster.example.com. 2024010101 7200 3600 1209600 300",
+      "example.com. 3600 IN NS ns1.example.com.",
+      "example.com. 3600 IN NS ns2.example.net.",
+      "ns1.example.com. 300 IN A 192.0.2.1",
+      "ns1.example.com. 3600 IN AAAA 2001:db8::1",
+      "www.example.com. 3600 IN CNAME example.com.",
+      "txt.sub.example.com. 60 IN TXT \"hello world\" \"second\" \"string\"",
+      "host-011.sub.example.com. 3600 IN A 10.0.0.1",
+      "host-013.sub.example.com. 3600 IN A 10.0.0.3",
+    ].iter().map(|r| r.parse().unwrap()).collect();
+    assert_eq!(records, expected, "Zone not parsed correctly");
+
+    // without $TTL, the last explicit TTL is inherited
+    let records = ZoneParser::new().with_origin("example.com".parse().unwrap()).parse("a 60 A 192.0.2.1\nb A 192.0.2.2").unwrap();
+    assert_eq!(records[1], Record::A { domain: "b.example.com".parse().unwrap(), addr: Ipv4Addr::new(192, 0, 2, 2), ttl: 60, class: RecordClass::IN });
+
+    let errors = [
+      ("a. 60 A 192.0.2.1\nb. 60 A 192.0.2.300", (2, 9)),
+      ("a. 60 A 192.0.2.1\n\nb. 60 IN ( A\n 192.0.2.1 192.0.2.2 )", (4, 12)),
+      ("a. A 192.0.2.1", (1, 4)),
+      ("  60 A 192.0.2.1", (1, 3)),
+      ("$ORIGIN example.com.\n$FOO bar", (2, 1)),
+      ("$GENERATE 5-1 a A 10.0.0.$", (1, 11)),
+      ("$GENERATE 1-1 h${9223372036854775807} 60 A 10.0.0.1", (1, 15)),
+      ("$GENERATE 1-1 h${0,10000000000} 60 A 10.0.0.1", (1, 15)),
+      ("$GENERATE 1-1 h${0,256} 60 A 10.0.0.1", (1, 15)),
+      ("$INCLUDE missing.zone", (1, 10)),
+    ];
+    for (text, position) in errors {
+      match ZoneParser::new().parse(text) {
+        Err(DrasilDNSError::Syntax { line, column, .. }) => assert_eq!((line, column), position, "Incorrect error position for {:?}", text),
+        res => panic!("Expected syntax error for {:?}, got {:?}", text, res),
+      }
+    }
+  }
+
+  #[test]
+  fn zone_include_and_write() {
+    let dir = std::env::temp_dir().join(format!("drasil-dns-zone-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("hosts.zone"), "$ORIGIN hosts\nb A 192.0.2.2\n").unwrap();
+    fs::write(dir.join("main.zone"), "$TTL 300\n$ORIGIN example.com.\n$INCLUDE hosts.zone\na A 192.0.2.1\n$INCLUDE hosts.zone other.net.\n").unwrap();
+
+    let records = ZoneParser::new().parse_file(dir.join("main.zone")).expect("Failed to parse zone with includes");
+    fs::remove_dir_all(&dir).unwrap();
+
+    let owners: Vec<String> = records.iter().map(|r| r.domain().to_string()).collect();
+    assert_eq!(owners, ["b.hosts.example.com.", "a.example.com.", "b.hosts.other.net."], "$INCLUDE not handled correctly");
+
+    let mut records = records;
+    records.push("example.com. 300 IN SOA ns1.example.com. hostmaster.example.com. 1 7200 3600 1209600 300".parse().unwrap());
+    records.push(records[1].clone());
+
+    let zone = write_zone(&records).expect("Failed to write zone");
+    assert_eq!(zone, "example.com.\t300\tIN\tSOA\tns1.example.com. hostmaster.example.com. 1 7200 3600 1209600 300\n\
+      a.example.com.\t300\tIN\tA\t192.0.2.1\n\
+      b.hosts.example.com.\t300\tIN\tA\t192.0.2.2\n\
+      b.hosts.other.net.\t300\tIN\tA\t192.0.2.2\n", "Zone not written correctly");
+
+    let mut reparsed = ZoneParser::new().parse(&zone).expect("Failed to parse written zone");
+    records.pop();
+    records.sort_by_key(|r| (r.record_type() != RecordType::SOA, r.domain().clone()));
+    reparsed.sort_by_key(|r| (r.record_type() != RecordType::SOA, r.domain().clone()));
+    assert_eq!(reparsed, records, "Records not equal after write+parse");
+  }
+}